@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::render::{Canvas, RenderTarget};
+use sdl2::surface::Surface;
+use typewriter::config::GridLayout;
+use typewriter::font;
+use typewriter::render::{draw_letter, GlyphAtlas};
+
+//Compares the cost of repainting the whole canvas, once by executing the instructions of every symbol and once by copying from the glyph atlas.
+//Draws onto a software canvas, so that no window is needed: cargo bench
+
+const REPAINTS: u32 = 200;
+
+fn main() -> Result<(), String> {
+	let font = font::load_font().map_err(|error| error.to_string())?;
+	let grid = GridLayout::default();
+	let mut canvas = Surface::new(grid.width(), grid.height(), PixelFormatEnum::RGB24)?.into_canvas()?;
+	let texture_creator = canvas.texture_creator();
+	let (color, background) = (Color::RGB(50, 255, 50), Color::RGB(10, 15, 10));
+	let atlas = GlyphAtlas::new(&texture_creator, &font, &[(color, background)])?;
+	
+	//Every slot of the canvas (including status line), cycling through all symbols:
+	let mut ids = font.symbols_by_id.keys().copied().collect::<Vec<u8>>();
+	ids.sort_unstable();
+	let slots = (0..=grid.status_row())
+		.flat_map(|y| (0..grid.char_width).map(move |x| (x, y)))
+		.zip(ids.iter().copied().cycle())
+		.collect::<Vec<_>>();
+	
+	let instructions = measure(&mut canvas, |canvas| {
+		for ((x, y), id) in &slots {
+			//The space clears the slot first, like the typewriter does:
+			draw_letter(canvas, &font.space, *x, *y, color, background);
+			draw_letter(canvas, &font.symbols_by_id[id], *x, *y, color, background);
+		}
+	});
+	let atlas = measure(&mut canvas, |canvas| {
+		for ((x, y), id) in &slots {
+			atlas.draw(canvas, *id, 0, *x, *y).expect("Failed to draw letter.");
+		}
+	});
+	println!("Full repaint of {} slots, average of {} repaints:", slots.len(), REPAINTS);
+	println!("  Instructions: {:>10.3?}", instructions);
+	println!("  Glyph atlas:  {:>10.3?}", atlas);
+	println!("  Speedup:      {:>9.1}x", instructions.as_secs_f64() / atlas.as_secs_f64());
+	Ok(())
+}
+
+fn measure<T: RenderTarget>(canvas: &mut Canvas<T>, mut repaint: impl FnMut(&mut Canvas<T>)) -> Duration {
+	//Warm up caches first:
+	repaint(canvas);
+	let start = Instant::now();
+	for _ in 0..REPAINTS {
+		repaint(canvas);
+	}
+	start.elapsed() / REPAINTS
+}
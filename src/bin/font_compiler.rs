@@ -0,0 +1,32 @@
+use std::fs::read;
+use typewriter::compiler::{compile_font, parse_ascii_glyphs, parse_pbm};
+
+//Compiles glyphs drawn as ASCII art or PBM image into a font file, which is written to standard output:
+// font-compiler glyphs.txt > Font.txt
+// font-compiler glyphs.pbm --keys ABC > Font.txt
+
+const USAGE: &str = "Usage: font-compiler <glyphs.txt|glyphs.pbm> [--keys <one key per glyph, for PBM images>]";
+
+fn main() -> Result<(), String> {
+	let mut args = std::env::args().skip(1);
+	let path = args.next().ok_or(USAGE)?;
+	let keys = match (args.next().as_deref(), args.next(), args.next()) {
+		(None, _, _) => None,
+		(Some("--keys"), Some(keys), None) => Some(keys),
+		_ => return Err(USAGE.to_string()),
+	};
+	
+	let bytes = read(&path).map_err(|error| format!("Failed to read '{}': {}", path, error))?;
+	let glyphs = if bytes.starts_with(b"P1") || bytes.starts_with(b"P4") {
+		parse_pbm(&bytes, &keys.ok_or("PBM images need --keys, to know which glyph is which.")?)
+	} else {
+		let text = String::from_utf8(bytes).map_err(|_| format!("'{}' is neither text nor PBM image", path))?;
+		parse_ascii_glyphs(&text)
+	}.map_err(|error| format!("{}: {}", path, error))?;
+	
+	let font = compile_font(&glyphs).map_err(|error| format!("{}: {}", path, error))?;
+	print!("{}", font);
+	let instructions = font.lines().filter(|line| !line.starts_with("key ")).count();
+	eprintln!("Compiled {} glyphs into {} instructions.", glyphs.len(), instructions);
+	Ok(())
+}
@@ -0,0 +1,32 @@
+use std::fs::read;
+use typewriter::font;
+use typewriter::import::{import_font, parse_bitmap_font, Fit};
+
+//Imports a BDF or PSF bitmap font, written to standard output as font file with key lines:
+// font-import font.bdf > Font.txt
+// font-import --crop font.psf > Font.txt
+//Glyphs bigger than the display glyph are scaled down, unless --crop is given.
+
+const USAGE: &str = "Usage: font-import [--crop] <font.bdf|font.psf>";
+
+fn main() -> Result<(), String> {
+	let mut fit = Fit::Scale;
+	let mut path = None;
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--crop" => fit = Fit::Crop,
+			_ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+			_ => return Err(USAGE.to_string()),
+		}
+	}
+	let path = path.ok_or(USAGE)?;
+	
+	let bytes = read(&path).map_err(|error| format!("Failed to read '{}': {}", path, error))?;
+	let source = parse_bitmap_font(&bytes).map_err(|error| format!("{}: {}", path, error))?;
+	let font = font::load_font().map_err(|error| error.to_string())?;
+	let text = import_font(&source, &path, &font, fit).map_err(|error| format!("{}: {}", path, error))?;
+	print!("{}", text);
+	let imported = text.lines().filter(|line| line.starts_with("key ")).count();
+	eprintln!("Imported {} of {} glyphs.", imported, font.highest_id);
+	Ok(())
+}
@@ -0,0 +1,51 @@
+use std::fs::read_to_string;
+use typewriter::font;
+use typewriter::lint::{lint_font, Finding, INSTRUCTION_BUDGET};
+
+//Checks a font for likely mistakes, one finding per line:
+// font-lint [--json] [--budget <instructions>] <font>
+//Instead of a path, 'built-in' checks the font of the binary. Exits with 1 if there are findings.
+
+const USAGE: &str = "Usage: font-lint [--json] [--budget <instructions per symbol>] <font|built-in>";
+
+fn main() -> Result<(), String> {
+	let mut json = false;
+	let mut budget = INSTRUCTION_BUDGET;
+	let mut path = None;
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--json" => json = true,
+			"--budget" => budget = args.next().and_then(|value| value.parse().ok()).ok_or(USAGE)?,
+			_ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+			_ => return Err(USAGE.to_string()),
+		}
+	}
+	let path = path.ok_or(USAGE)?;
+	
+	let (file, text) = if path == "built-in" {
+		(font::DEFAULT_FONT_NAME.to_string(), font::DEFAULT_FONT.to_string())
+	} else {
+		let text = read_to_string(&path).map_err(|error| format!("Failed to read '{}': {}", path, error))?;
+		(path, text)
+	};
+	let findings = match font::parse_font_symbols(&text, &file) {
+		Ok(symbols) => lint_font(&symbols, budget),
+		Err(error) => vec![Finding::from_load_error(&error)],
+	};
+	
+	for finding in &findings {
+		if json {
+			println!("{}", finding.to_json(&file));
+		} else {
+			println!("{}:{}", file, finding);
+		}
+	}
+	if !findings.is_empty() {
+		if !json {
+			println!("{} findings", findings.len());
+		}
+		std::process::exit(1);
+	}
+	Ok(())
+}
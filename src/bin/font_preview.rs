@@ -0,0 +1,40 @@
+use std::path::Path;
+use typewriter::font;
+use typewriter::font::{Font, FontError};
+use typewriter::preview::{diff_fonts, preview_font};
+
+//Prints the glyphs of a font as ASCII art, or the glyphs which changed between two fonts:
+// font-preview [font]
+// font-preview --diff <old font> <new font>
+//Instead of a path, 'built-in' uses the font of the binary. Without any font, the built-in one is printed.
+
+const USAGE: &str = "Usage: font-preview [<font>] | font-preview --diff <old font> <new font>";
+
+fn load(path: &str) -> Result<Font, FontError> {
+	if path == "built-in" {
+		font::load_font()
+	} else {
+		font::load_font_file(Path::new(path))
+	}
+}
+
+fn main() -> Result<(), String> {
+	let args = std::env::args().skip(1).collect::<Vec<String>>();
+	let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+	match args.as_slice() {
+		[] => print!("{}", preview_font(&load("built-in").map_err(|error| error.to_string())?)),
+		["--diff", old, new] => {
+			let old = load(old).map_err(|error| error.to_string())?;
+			let new = load(new).map_err(|error| error.to_string())?;
+			let diff = diff_fonts(&old, &new);
+			if diff.is_empty() {
+				println!("Both fonts draw the same glyphs.");
+			} else {
+				print!("{}", diff);
+			}
+		}
+		[path] if !path.starts_with("--") => print!("{}", preview_font(&load(path).map_err(|error| error.to_string())?)),
+		_ => return Err(USAGE.to_string()),
+	}
+	Ok(())
+}
@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+///Source of the current time, so that everything depending on time (like blinking) can be tested.
+pub trait Clock {
+	///Time since some fixed point, which never changes.
+	fn now(&self) -> Duration;
+}
+
+///The real time, counted from creation.
+pub struct SystemClock {
+	start: Instant,
+}
+
+impl Default for SystemClock {
+	fn default() -> Self {
+		Self {
+			start: Instant::now(),
+		}
+	}
+}
+
+impl Clock for SystemClock {
+	fn now(&self) -> Duration {
+		self.start.elapsed()
+	}
+}
+
+///Time only passes when told to, for deterministic frames.
+#[derive(Default)]
+pub struct ManualClock {
+	now: Duration,
+}
+
+impl ManualClock {
+	pub fn advance(&mut self, duration: Duration) {
+		self.now += duration;
+	}
+}
+
+impl Clock for ManualClock {
+	fn now(&self) -> Duration {
+		self.now
+	}
+}
@@ -0,0 +1,85 @@
+use std::fmt::{Display, Formatter};
+use crate::config::GridLayout;
+use crate::typewriter::LetterInstruction;
+
+///How many letters the typewriter asked to draw and how many of them actually had to be drawn.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct CoalesceStats {
+	pub received: u64,
+	pub drawn: u64,
+}
+
+impl CoalesceStats {
+	pub fn saved(&self) -> u64 {
+		self.received - self.drawn
+	}
+}
+
+impl Display for CoalesceStats {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let percentage = if self.received == 0 { 0.0 } else { self.saved() as f64 * 100.0 / self.received as f64 };
+		write!(f, "Drew {} of {} letters, saved {} ({:.1}%)", self.drawn, self.received, self.saved(), percentage)
+	}
+}
+
+///Reduces a batch of instructions to the final symbol of each slot, dropping slots which already show that symbol.
+///Remembers what is on the canvas (including status line) in a shadow grid, which starts out empty like the canvas.
+///Only valid when drawing a letter replaces the whole slot, like the glyph atlas does.
+pub struct Coalescer {
+	width: usize,
+	shown: Vec<u8>,
+	//Final symbol of each slot in the current batch:
+	pending: Vec<Option<u8>>,
+	pub stats: CoalesceStats,
+}
+
+impl Coalescer {
+	pub fn new(grid: &GridLayout) -> Self {
+		let slots = grid.char_width * (grid.status_row() + 1);
+		Self {
+			width: grid.char_width,
+			shown: vec![0; slots],
+			pending: vec![None; slots],
+			stats: CoalesceStats::default(),
+		}
+	}
+	
+	///Forgets what is on the canvas, for when it was replaced by an empty one. Keeps the statistics.
+	pub fn reset(&mut self, grid: &GridLayout) {
+		*self = Self {
+			stats: self.stats,
+			..Self::new(grid)
+		};
+	}
+	
+	///Replaces the instructions with the coalesced ones. Instructions outside of the canvas are kept, to be reported when drawn.
+	pub fn coalesce(&mut self, letter_instructions: &mut Vec<LetterInstruction>) {
+		self.stats.received += letter_instructions.len() as u64;
+		let mut touched = Vec::new();
+		let mut outside = Vec::new();
+		for instruction in letter_instructions.drain(..) {
+			let index = instruction.pos_y * self.width + instruction.pos_x;
+			if instruction.pos_x >= self.width || index >= self.pending.len() {
+				outside.push(instruction);
+				continue;
+			}
+			if self.pending[index].is_none() {
+				touched.push(index);
+			}
+			self.pending[index] = Some(instruction.id);
+		}
+		for index in touched {
+			let id = self.pending[index].take().expect("Touched slots always have a pending symbol.");
+			if self.shown[index] != id {
+				self.shown[index] = id;
+				letter_instructions.push(LetterInstruction {
+					pos_x: index % self.width,
+					pos_y: index / self.width,
+					id,
+				});
+			}
+		}
+		letter_instructions.extend(outside);
+		self.stats.drawn += letter_instructions.len() as u64;
+	}
+}
@@ -0,0 +1,327 @@
+use std::fmt::{Display, Formatter};
+use crate::font::{format_symbol, parse_font, FontError, Instruction, SymbolDescription};
+use crate::glyph::{rasterize, Bitmap, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+//Turns glyphs drawn as bitmaps into font instructions, using as few instructions as it can find.
+//Drawing instructions never draw outside of the glyph and clearing instructions never clear inside of it. So overlapping is fine, every pixel just has to be covered once.
+
+//Limit for the exhaustive search, after that the best solution found so far is used:
+const SEARCH_BUDGET: usize = 200_000;
+//How many of the instructions to carve from are searched, after estimating all of them:
+const CARVE_CANDIDATES: usize = 8;
+//Every pixel of a glyph:
+const ALL_PIXELS: u64 = (1 << (GLYPH_WIDTH * GLYPH_HEIGHT)) - 1;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CompileError {
+	///Line number and content of a line which is neither key line nor bitmap row.
+	InvalidLine(usize, String),
+	///Key and number of rows it got.
+	WrongRowCount(char, usize),
+	DuplicateKey(char),
+	///Only space can be empty, and it is built-in.
+	EmptyGlyph(char),
+	InvalidPbm(String),
+	WrongKeyCount {
+		expected: usize,
+		actual: usize,
+	},
+	///The compiled font could not be loaded, for example because of a reserved key.
+	Font(FontError),
+	///The instructions do not draw the glyph, should never happen.
+	Mismatch(char),
+}
+
+impl Display for CompileError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CompileError::InvalidLine(line, text) => write!(f, "Line {}: Expected 'key X' or a row of {} '#'/'.', got '{}'", line, GLYPH_WIDTH, text),
+			CompileError::WrongRowCount(key, rows) => write!(f, "Glyph '{}' has {} rows, expected {}", key, rows, GLYPH_HEIGHT),
+			CompileError::DuplicateKey(key) => write!(f, "Glyph '{}' is defined more than once", key),
+			CompileError::EmptyGlyph(key) => write!(f, "Glyph '{}' is empty, only space can be empty", key),
+			CompileError::InvalidPbm(message) => write!(f, "Invalid PBM image: {}", message),
+			CompileError::WrongKeyCount { expected, actual } => write!(f, "Image has {} glyphs, but {} keys were given", expected, actual),
+			CompileError::Font(error) => write!(f, "Compiled font does not load: {}", error),
+			CompileError::Mismatch(key) => write!(f, "Instructions of glyph '{}' do not draw its bitmap", key),
+		}
+	}
+}
+
+///Glyphs drawn like this, rows from top to bottom:
+/// key A
+/// .###.
+/// #...#
+/// ...
+///Empty lines and comment lines starting with '//' are ignored.
+pub fn parse_ascii_glyphs(text: &str) -> Result<Vec<(char, Bitmap)>, CompileError> {
+	let mut glyphs: Vec<(char, Vec<[bool; GLYPH_WIDTH]>)> = Vec::new();
+	for (index, line) in text.lines().enumerate() {
+		let line = line.strip_suffix('\r').unwrap_or(line);
+		if line.is_empty() || line.starts_with("//") {
+			continue;
+		}
+		let invalid = || CompileError::InvalidLine(index + 1, line.to_string());
+		if let Some(key) = line.strip_prefix("key ") {
+			let mut chars = key.chars();
+			let (Some(key), None) = (chars.next(), chars.next()) else {
+				return Err(invalid());
+			};
+			glyphs.push((key, Vec::new()));
+			continue;
+		}
+		if line.chars().count() != GLYPH_WIDTH {
+			return Err(invalid());
+		}
+		let mut row = [false; GLYPH_WIDTH];
+		for (pixel, c) in row.iter_mut().zip(line.chars()) {
+			*pixel = match c {
+				'#' => true,
+				'.' => false,
+				_ => return Err(invalid()),
+			};
+		}
+		glyphs.last_mut().ok_or_else(invalid)?.1.push(row);
+	}
+	glyphs.into_iter().map(|(key, rows)| {
+		let bitmap: Bitmap = rows.as_slice().try_into().map_err(|_| CompileError::WrongRowCount(key, rows.len()))?;
+		Ok((key, bitmap))
+	}).collect()
+}
+
+///Strip of glyphs next to each other, as plain (P1) or raw (P4) PBM image which is 11 pixels high. Black pixels are drawn.
+pub fn parse_pbm(bytes: &[u8], keys: &str) -> Result<Vec<(char, Bitmap)>, CompileError> {
+	let invalid = |message: &str| CompileError::InvalidPbm(message.to_string());
+	//Header: Magic number, width and height, separated by whitespace and comments:
+	let mut position = 0;
+	let mut next_token = || {
+		loop {
+			while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+				position += 1;
+			}
+			if position < bytes.len() && bytes[position] == b'#' {
+				while position < bytes.len() && bytes[position] != b'\n' {
+					position += 1;
+				}
+				continue;
+			}
+			break;
+		}
+		let start = position;
+		while position < bytes.len() && !bytes[position].is_ascii_whitespace() {
+			position += 1;
+		}
+		std::str::from_utf8(&bytes[start..position]).unwrap_or("").to_string()
+	};
+	let magic = next_token();
+	let width: usize = next_token().parse().map_err(|_| invalid("width is not a number"))?;
+	let height: usize = next_token().parse().map_err(|_| invalid("height is not a number"))?;
+	if height != GLYPH_HEIGHT || width == 0 || !width.is_multiple_of(GLYPH_WIDTH) {
+		return Err(CompileError::InvalidPbm(format!("expected a height of {} and a width of a multiple of {}, got {}x{}", GLYPH_HEIGHT, GLYPH_WIDTH, width, height)));
+	}
+	
+	let pixels: Vec<bool> = match magic.as_str() {
+		"P1" => bytes[position..].iter()
+			.filter(|byte| !byte.is_ascii_whitespace())
+			.map(|byte| match byte {
+				b'1' => Ok(true),
+				b'0' => Ok(false),
+				_ => Err(invalid("pixels have to be 0 or 1")),
+			})
+			.collect::<Result<_, _>>()?,
+		"P4" => {
+			//Exactly one whitespace after the header, then rows padded to full bytes:
+			let data = bytes.get(position + 1..).unwrap_or(&[]);
+			let row_bytes = width.div_ceil(8);
+			if data.len() < row_bytes * height {
+				return Err(invalid("image data is too short"));
+			}
+			(0..height).flat_map(|y| (0..width).map(move |x| data[y * row_bytes + x / 8] & (0x80 >> (x % 8)) != 0)).collect()
+		}
+		_ => return Err(invalid("only P1 and P4 images are supported")),
+	};
+	if pixels.len() != width * height {
+		return Err(CompileError::InvalidPbm(format!("expected {} pixels, got {}", width * height, pixels.len())));
+	}
+	
+	let count = width / GLYPH_WIDTH;
+	if keys.chars().count() != count {
+		return Err(CompileError::WrongKeyCount {
+			expected: count,
+			actual: keys.chars().count(),
+		});
+	}
+	Ok(keys.chars().enumerate().map(|(glyph, key)| {
+		let mut bitmap = [[false; GLYPH_WIDTH]; GLYPH_HEIGHT];
+		for (y, row) in bitmap.iter_mut().enumerate() {
+			for (x, pixel) in row.iter_mut().enumerate() {
+				*pixel = pixels[y * width + glyph * GLYPH_WIDTH + x];
+			}
+		}
+		(key, bitmap)
+	}).collect())
+}
+
+///Compiles all glyphs into a font file with key lines. The result is loaded again and compared with the bitmaps.
+pub fn compile_font(glyphs: &[(char, Bitmap)]) -> Result<String, CompileError> {
+	let mut text = String::new();
+	for (index, (key, bitmap)) in glyphs.iter().enumerate() {
+		if glyphs[..index].iter().any(|(other, _)| other == key) {
+			return Err(CompileError::DuplicateKey(*key));
+		}
+		let symbol = compile_glyph(bitmap).ok_or(CompileError::EmptyGlyph(*key))?;
+		if rasterize(&symbol) != *bitmap {
+			return Err(CompileError::Mismatch(*key));
+		}
+		text.push_str(&format_symbol(*key, &symbol));
+	}
+	
+	let font = parse_font(&text, "compiled font").map_err(CompileError::Font)?;
+	for (key, bitmap) in glyphs {
+		if font.symbols_by_key.get(key).map(|symbol| rasterize(symbol)) != Some(*bitmap) {
+			return Err(CompileError::Mismatch(*key));
+		}
+	}
+	Ok(text)
+}
+
+///Near minimal instructions drawing exactly the bitmap, None if it is empty.
+///Either only draws, or first draws an instruction which is too big and later clears the pixels which should not be drawn.
+pub fn compile_glyph(bitmap: &Bitmap) -> Option<SymbolDescription> {
+	let target = mask(bitmap);
+	if target == 0 {
+		return None;
+	}
+	let instructions = all_instructions();
+	let draws = maximal_within(&instructions, target);
+	let mut best = cover(target, &draws, SEARCH_BUDGET).expect("Every pixel is covered by a point.");
+	
+	//Clears may only remove pixels which are not part of the glyph:
+	let clears = maximal_within(&instructions, ALL_PIXELS & !target).into_iter()
+		.map(|(instruction, pixels)| (Instruction { clear: true, ..instruction }, pixels))
+		.filter(|(instruction, _)| instruction.encode(false).contains('1'))
+		.collect::<Vec<_>>();
+	//The instruction to carve from should draw more wanted than unwanted pixels. Estimate each greedily, then search the best ones:
+	let carve = |first: Instruction, pixels: u64, budget: usize| {
+		let draw = cover(target & !pixels, &draws, budget)?;
+		let clear = cover(pixels & !target, &clears, budget)?;
+		Some([vec![first], draw, clear].concat())
+	};
+	let mut estimates = instructions.iter()
+		.filter(|(_, pixels)| pixels & !target != 0 && (pixels & target).count_ones() > (pixels & !target).count_ones())
+		.filter_map(|(first, pixels)| carve(*first, *pixels, 0).map(|program| (program.len(), *first, *pixels)))
+		.collect::<Vec<_>>();
+	estimates.sort_by_key(|(length, _, _)| *length);
+	for (_, first, pixels) in estimates.into_iter().take(CARVE_CANDIDATES) {
+		if let Some(program) = carve(first, pixels, SEARCH_BUDGET / CARVE_CANDIDATES) {
+			if program.len() < best.len() {
+				best = program;
+			}
+		}
+	}
+	Some(best)
+}
+
+///Near minimal instructions which together contain every needed pixel, None if that is not possible with the candidates.
+///Greedy first to have an upper bound, which is then improved by searching until the budget is used up.
+fn cover(need: u64, candidates: &[(Instruction, u64)], budget: usize) -> Option<Vec<Instruction>> {
+	if candidates.iter().fold(0, |all, (_, pixels)| all | pixels) & need != need {
+		return None;
+	}
+	let mut best = Vec::new();
+	let mut covered = 0;
+	while covered & need != need {
+		let (index, _) = candidates.iter().enumerate()
+			.max_by_key(|(_, (_, pixels))| (pixels & need & !covered).count_ones())
+			.expect("Candidates cover every needed pixel.");
+		best.push(index);
+		covered |= candidates[index].1;
+	}
+	
+	let mut chosen = Vec::new();
+	let mut budget = budget;
+	search(need, 0, candidates, &mut chosen, &mut best, &mut budget);
+	Some(best.into_iter().map(|index| candidates[index].0).collect())
+}
+
+///Depth first search over the instructions covering the lowest pixel that is not covered yet.
+fn search(need: u64, covered: u64, candidates: &[(Instruction, u64)], chosen: &mut Vec<usize>, best: &mut Vec<usize>, budget: &mut usize) {
+	if covered & need == need {
+		if chosen.len() < best.len() {
+			*best = chosen.clone();
+		}
+		return;
+	}
+	if chosen.len() + 1 >= best.len() || *budget == 0 {
+		return;
+	}
+	*budget -= 1;
+	let pixel = (need & !covered).trailing_zeros();
+	for (index, (_, pixels)) in candidates.iter().enumerate() {
+		if pixels & (1 << pixel) != 0 {
+			chosen.push(index);
+			search(need, covered | pixels, candidates, chosen, best, budget);
+			chosen.pop();
+		}
+	}
+}
+
+///Pixel Y X as bit Y * width + X, with Y counting from the bottom like instructions do.
+fn mask(bitmap: &Bitmap) -> u64 {
+	let mut mask = 0;
+	for (row, pixels) in bitmap.iter().enumerate() {
+		for (x, pixel) in pixels.iter().enumerate() {
+			if *pixel {
+				mask |= 1 << ((GLYPH_HEIGHT - 1 - row) * GLYPH_WIDTH + x);
+			}
+		}
+	}
+	mask
+}
+
+///Every drawing instruction with the pixels it draws, only one instruction per set of pixels.
+fn all_instructions() -> Vec<(Instruction, u64)> {
+	//Start, end and fill of one axis. Unfilled ranges with start == end are a single coordinate:
+	let ranges = |size: u32| {
+		let mut ranges = Vec::new();
+		for start in 0..size {
+			for end in start..size {
+				ranges.push((start, end, false));
+				if end > start {
+					ranges.push((start, end, true));
+				}
+			}
+		}
+		ranges
+	};
+	let mut instructions: Vec<(Instruction, u64)> = Vec::new();
+	for (y_start, y_end, y_fill) in ranges(GLYPH_HEIGHT as u32) {
+		for (x_start, x_end, x_fill) in ranges(GLYPH_WIDTH as u32) {
+			let instruction = Instruction {
+				clear: false,
+				x_start,
+				x_fill,
+				x_end,
+				y_start,
+				y_fill,
+				y_end,
+			};
+			let pixels = mask(&rasterize(&[instruction]));
+			if !instructions.iter().any(|(_, other)| *other == pixels) {
+				instructions.push((instruction, pixels));
+			}
+		}
+	}
+	instructions
+}
+
+///Every instruction which only touches allowed pixels and is not contained in another such instruction.
+///Using only those loses nothing, as any instruction can be replaced by a bigger one containing it.
+fn maximal_within(instructions: &[(Instruction, u64)], allowed: u64) -> Vec<(Instruction, u64)> {
+	let inside = instructions.iter().copied().filter(|(_, pixels)| pixels & !allowed == 0).collect::<Vec<_>>();
+	let mut candidates = inside.iter().copied()
+		.filter(|(_, pixels)| !inside.iter().any(|(_, other)| other != pixels && other & pixels == *pixels))
+		.collect::<Vec<_>>();
+	//Biggest first, so that the search finds good solutions early:
+	candidates.sort_by_key(|(_, pixels)| std::cmp::Reverse(pixels.count_ones()));
+	candidates
+}
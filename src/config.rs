@@ -1,15 +1,208 @@
-//Stuff to change, enjoy:
+use std::path::PathBuf;
+use std::time::Duration;
+use crate::cursor::CursorStyle;
+
+//Stuff to change, enjoy (these are only the defaults, see the command line flags in Options::from_args):
 pub const TARGET_DISPLAY : u32 = 1;
 pub const PIXEL_SIDE: u32 = 6; //8
 pub const CHAR_WIDTH: usize = 42; //42
 pub const CHAR_HEIGHT: usize = 10; //10
 pub const MEMORY_SIZE : usize = 0x1000;
+pub const GUTTER_WIDTH: usize = 5; //Line numbers left of the text, 0 to disable them.
+pub const CURSOR_STYLE: CursorStyle = CursorStyle::Bar;
+pub const BLINK_PERIOD_MS: u64 = 1000; //0 to not blink at all.
 
 //Adjust if needed...
 pub const NEWLINE : u8 = -1i8 as u8;
+pub const REPLACEMENT : u8 = -2i8 as u8; //Letters the font does not have, drawn as replacement symbol.
+pub const SPACE : u8 = 0;
+
+//Size of one letter slot in pixels:
+pub const SLOT_WIDTH: usize = 6;
+pub const SLOT_HEIGHT: usize = 12;
+
+pub const MAX_PIXEL_SIDE: u32 = 64; //Upper limit for zooming in.
+pub const MIN_CHAR_WIDTH: usize = 2; //Two columns are needed for the scroll markers without wrapping.
+pub const MIN_CHAR_HEIGHT: usize = 1;
+
+pub const USAGE: &str = "Usage: typewriter [--width <columns>] [--height <rows>] [--gutter <columns>] [--scale <pixels>] [--display <index>] [--resize letterbox|reflow] [--cursor bar|underline|block] [--blink <milliseconds>] [--font <path>] [--themes <path>]";
+
+///Everything that can be chosen with command line flags.
+pub struct Options {
+	pub grid: GridLayout,
+	pub cursor_style: CursorStyle,
+	///None to not blink at all.
+	pub blink_period: Option<Duration>,
+	///Font file to use instead of the built-in font.
+	pub font_path: Option<PathBuf>,
+	///Theme file with themes in addition to the built-in ones.
+	pub theme_path: Option<PathBuf>,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Self {
+			grid: GridLayout::default(),
+			cursor_style: CURSOR_STYLE,
+			blink_period: blink_period(BLINK_PERIOD_MS),
+			font_path: None,
+			theme_path: None,
+		}
+	}
+}
+
+impl Options {
+	///Parses command line flags, every flag not given keeps its default.
+	pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+		let mut options = Self::default();
+		let grid = &mut options.grid;
+		let mut args = args.into_iter();
+		while let Some(flag) = args.next() {
+			let value = args.next().ok_or_else(|| format!("Missing value for '{}'", flag))?;
+			let invalid = || format!("Invalid value '{}' for '{}'", value, flag);
+			match flag.as_str() {
+				"--width" => grid.char_width = value.parse().map_err(|_| invalid())?,
+				"--height" => grid.char_height = value.parse().map_err(|_| invalid())?,
+				"--gutter" => grid.gutter_width = value.parse().map_err(|_| invalid())?,
+				"--scale" => grid.pixel_side = value.parse().map_err(|_| invalid())?,
+				"--display" => grid.target_display = value.parse().map_err(|_| invalid())?,
+				"--resize" => grid.resize_mode = match value.as_str() {
+					"letterbox" => ResizeMode::Letterbox,
+					"reflow" => ResizeMode::Reflow,
+					_ => return Err(invalid()),
+				},
+				"--cursor" => options.cursor_style = CursorStyle::parse(&value).ok_or_else(invalid)?,
+				"--blink" => options.blink_period = blink_period(value.parse().map_err(|_| invalid())?),
+				"--font" => options.font_path = Some(PathBuf::from(value)),
+				"--themes" => options.theme_path = Some(PathBuf::from(value)),
+				_ => return Err(format!("Unknown flag '{}'", flag)),
+			}
+		}
+		options.grid = options.grid.validate()?;
+		Ok(options)
+	}
+}
+
+fn blink_period(milliseconds: u64) -> Option<Duration> {
+	if milliseconds == 0 {
+		None
+	} else {
+		Some(Duration::from_millis(milliseconds))
+	}
+}
+
+///What happens to the text grid, when the window size changes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ResizeMode {
+	///The grid stays the same, it is scaled by the largest integer factor that fits and centered.
+	Letterbox,
+	///The grid gets as many rows and columns as fit into the window, the document is laid out again.
+	Reflow,
+}
+
+///Dimensions of the emulated display, chosen at startup. The text grid is what the typewriter draws onto.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GridLayout {
+	///Letters per row.
+	pub char_width: usize,
+	///Rows of text, the status line is drawn as additional row below.
+	pub char_height: usize,
+	///Columns left of the text used for line numbers, 0 to disable them.
+	pub gutter_width: usize,
+	///Side length of one display pixel in screen pixels.
+	pub pixel_side: u32,
+	///Display to center the window on, if it exists.
+	pub target_display: u32,
+	pub resize_mode: ResizeMode,
+}
+
+impl Default for GridLayout {
+	fn default() -> Self {
+		Self {
+			char_width: CHAR_WIDTH,
+			char_height: CHAR_HEIGHT,
+			gutter_width: GUTTER_WIDTH,
+			pixel_side: PIXEL_SIDE,
+			target_display: TARGET_DISPLAY,
+			resize_mode: ResizeMode::Letterbox,
+		}
+	}
+}
 
-//Calculated:
-pub const WIDTH: u32 = (1 + CHAR_WIDTH * 6) as u32;
-pub const HEIGHT: u32 = (1 + CHAR_HEIGHT * 12) as u32;
-pub const CHAR_WIDTH_UPPER_BOUND: usize = CHAR_WIDTH - 1;
-pub const CHAR_HEIGHT_UPPER_BOUND: usize = CHAR_HEIGHT - 1;
+impl GridLayout {
+	///Text grid with the default gutter, pixel size and display.
+	pub fn with_size(char_width: usize, char_height: usize) -> Result<Self, String> {
+		Self {
+			char_width,
+			char_height,
+			..Self::default()
+		}.validate()
+	}
+	
+	///Same grid with a bigger or smaller pixel size, staying within the allowed scales.
+	pub fn zoomed(&self, steps: i32) -> Self {
+		Self {
+			pixel_side: self.pixel_side.saturating_add_signed(steps).clamp(1, MAX_PIXEL_SIDE),
+			..*self
+		}
+	}
+	
+	///Grid with as many rows and columns as fit into a window of the given size (in screen pixels) at the current scale.
+	///Never gets smaller than the minimum grid, even if the window is.
+	pub fn reflowed(&self, window_width: u32, window_height: u32) -> Self {
+		let columns = (window_width / self.pixel_side).saturating_sub(1) as usize / SLOT_WIDTH;
+		let rows = (window_height / self.pixel_side).saturating_sub(1) as usize / SLOT_HEIGHT;
+		Self {
+			char_width: columns.saturating_sub(self.gutter_width).max(MIN_CHAR_WIDTH),
+			char_height: rows.saturating_sub(1).max(MIN_CHAR_HEIGHT), //One row is the status line.
+			..*self
+		}
+	}
+	
+	fn validate(self) -> Result<Self, String> {
+		if self.char_width < MIN_CHAR_WIDTH {
+			return Err(format!("Width has to be at least {} columns, got {}", MIN_CHAR_WIDTH, self.char_width));
+		}
+		if self.char_height < MIN_CHAR_HEIGHT {
+			return Err(format!("Height has to be at least {} row", MIN_CHAR_HEIGHT));
+		}
+		//One column is always left empty towards the text:
+		if self.gutter_width == 1 {
+			return Err("Gutter has to be either 0 or at least 2 columns".to_string());
+		}
+		if self.pixel_side < 1 || self.pixel_side > MAX_PIXEL_SIDE {
+			return Err(format!("Scale has to be between 1 and {}", MAX_PIXEL_SIDE));
+		}
+		//Keep the window size within what SDL accepts:
+		if self.gutter_width.saturating_add(self.char_width) > (u16::MAX as usize - 1) / SLOT_WIDTH
+			|| self.char_height > (u16::MAX as usize - 1) / SLOT_HEIGHT - 1 {
+			return Err(format!("Grid of {}x{} letters is too big", self.char_width, self.char_height));
+		}
+		Ok(self)
+	}
+	
+	//Calculated:
+	
+	///The status line is drawn as additional row below the text.
+	pub fn status_row(&self) -> usize {
+		self.char_height
+	}
+	
+	///Width in display pixels, including gutter.
+	pub fn width(&self) -> u32 {
+		(1 + (self.gutter_width + self.char_width) * SLOT_WIDTH) as u32
+	}
+	
+	///Height in display pixels, including status line.
+	pub fn height(&self) -> u32 {
+		(1 + (self.status_row() + 1) * SLOT_HEIGHT) as u32
+	}
+	
+	pub fn char_width_upper_bound(&self) -> usize {
+		self.char_width - 1
+	}
+	
+	pub fn char_height_upper_bound(&self) -> usize {
+		self.char_height - 1
+	}
+}
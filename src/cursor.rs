@@ -1,20 +1,106 @@
-use crate::config::{CHAR_HEIGHT_UPPER_BOUND, CHAR_WIDTH_UPPER_BOUND, CHAR_WIDTH};
+use std::time::Duration;
+use crate::config::{GridLayout, SLOT_HEIGHT, SLOT_WIDTH};
+use crate::error::Error;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CursorStyle {
+	///Vertical line before the letter.
+	Bar,
+	///Horizontal line below the letter.
+	Underline,
+	///Filled slot, the letter inside is drawn inverted.
+	Block,
+}
+
+impl CursorStyle {
+	pub fn parse(name: &str) -> Option<Self> {
+		match name {
+			"bar" => Some(CursorStyle::Bar),
+			"underline" => Some(CursorStyle::Underline),
+			"block" => Some(CursorStyle::Block),
+			_ => None,
+		}
+	}
+	
+	///Rectangle (x, y, width, height) in display pixels covered by the cursor at the given grid position.
+	pub fn rect(&self, x: usize, y: usize) -> (i32, i32, u32, u32) {
+		//Letters are drawn 1 pixel right of the slot start and occupy 5x11 pixels, the baseline is the 8th pixel row:
+		let (x, y) = ((x * SLOT_WIDTH) as i32, (y * SLOT_HEIGHT) as i32);
+		match self {
+			CursorStyle::Bar => (x, y + 1, 1, 8),
+			CursorStyle::Underline => (x + 1, y + 9, 5, 1),
+			CursorStyle::Block => (x, y + 1, 7, 11),
+		}
+	}
+}
+
+///Decides whether the cursor is visible. It is visible for the first half of each period and stays visible after typing.
+pub struct CursorBlink {
+	///None to never blink.
+	period: Option<Duration>,
+	since: Duration,
+}
+
+impl CursorBlink {
+	pub fn new(period: Option<Duration>) -> Self {
+		Self {
+			period,
+			since: Duration::ZERO,
+		}
+	}
+	
+	///Starts a new period, so that the cursor does not vanish while typing.
+	pub fn reset(&mut self, now: Duration) {
+		self.since = now;
+	}
+	
+	///Time until the cursor appears or vanishes next, None if it never does.
+	pub fn until_change(&self, now: Duration) -> Option<Duration> {
+		let period = self.period.filter(|period| !period.is_zero())?.as_nanos();
+		let phase = now.saturating_sub(self.since).as_nanos() % period;
+		let remaining = if phase < period / 2 { period / 2 - phase } else { period - phase };
+		Some(Duration::from_nanos(remaining as u64))
+	}
+	
+	pub fn is_visible(&self, now: Duration) -> bool {
+		match self.period {
+			Some(period) if !period.is_zero() => (now.saturating_sub(self.since).as_nanos() % period.as_nanos()) < period.as_nanos() / 2,
+			_ => true,
+		}
+	}
+}
 
-#[derive(Default)]
 pub struct CursorPointer {
+	grid: GridLayout,
 	x_backup: usize,
 	y_backup: usize,
 	pub x: usize,
 	pub y: usize,
 }
 
+impl Default for CursorPointer {
+	fn default() -> Self {
+		Self::new(GridLayout::default())
+	}
+}
+
 impl CursorPointer {
+	pub fn new(grid: GridLayout) -> Self {
+		Self {
+			grid,
+			x_backup: 0,
+			y_backup: 0,
+			x: 0,
+			y: 0,
+		}
+	}
+	
 	pub fn increment(&mut self) {
 		self.x += 1;
-		if self.x > CHAR_WIDTH {
+		if self.x > self.grid.char_width {
 			self.x = 1; //Skip 0, as we just drew a letter.
 			self.y += 1;
-			if self.y > CHAR_HEIGHT_UPPER_BOUND {
+			if self.y > self.grid.char_height_upper_bound() {
 				self.y = 0;
 			}
 		}
@@ -22,9 +108,9 @@ impl CursorPointer {
 	
 	pub fn decrement(&mut self) {
 		if self.x <= 1 {
-			self.x = CHAR_WIDTH;
+			self.x = self.grid.char_width;
 			if self.y == 0 {
-				self.y = CHAR_HEIGHT_UPPER_BOUND;
+				self.y = self.grid.char_height_upper_bound();
 			} else {
 				self.y -= 1;
 			}
@@ -35,12 +121,12 @@ impl CursorPointer {
 	
 	/// Go to the previous drawing slot, not the previous cursor position.
 	/// # Remarks
-	/// Has to be handled differently, due to the WIDTH having an additional cursor position.
+	/// Has to be handled differently, due to the width having an additional cursor position.
 	pub fn decrement_slot(&mut self) {
 		if self.x < 1 {
-			self.x = CHAR_WIDTH_UPPER_BOUND;
+			self.x = self.grid.char_width_upper_bound();
 			if self.y == 0 {
-				self.y = CHAR_HEIGHT_UPPER_BOUND;
+				self.y = self.grid.char_height_upper_bound();
 			} else {
 				self.y -= 1;
 			}
@@ -64,11 +150,11 @@ impl CursorPointer {
 	}
 	
 	pub fn is_at_canvas_end(&self) -> bool {
-		self.x == CHAR_WIDTH && self.y == CHAR_HEIGHT_UPPER_BOUND
+		self.x == self.grid.char_width && self.y == self.grid.char_height_upper_bound()
 	}
 	
 	pub fn is_at_line_end(&self) -> bool {
-		self.x == CHAR_WIDTH_UPPER_BOUND
+		self.x == self.grid.char_width_upper_bound()
 	}
 	
 	pub fn is_at_line_start(&self) -> bool {
@@ -76,7 +162,7 @@ impl CursorPointer {
 	}
 	
 	pub fn is_last_line(&self) -> bool {
-		self.y == CHAR_HEIGHT_UPPER_BOUND
+		self.y == self.grid.char_height_upper_bound()
 	}
 	
 	pub fn is_first_line(&self) -> bool {
@@ -101,15 +187,17 @@ impl CursorPointer {
 		(self.x, self.y)
 	}
 	
-	pub fn get_draw_letter_position(&self) -> (usize, usize) {
-		if self.x == CHAR_WIDTH {
+	pub fn get_draw_letter_position(&self) -> Result<(usize, usize), Error> {
+		if self.x == self.grid.char_width {
 			//Assume that there always is a next line. Because else special handling should be performed and this function never called directly.
-			if self.y == CHAR_HEIGHT_UPPER_BOUND {
-				println!("VIOLATION: Before drawing a letter, make sure that there is a next line accessible when the cursor is at the end of a line!");
-				return (0, self.y);
+			if self.y == self.grid.char_height_upper_bound() {
+				return Err(Error::OutOfCanvas {
+					x: 0,
+					y: self.y + 1,
+				});
 			}
-			return (0, self.y + 1);
+			return Ok((0, self.y + 1));
 		}
-		(self.x, self.y)
+		Ok((self.x, self.y))
 	}
 }
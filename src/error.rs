@@ -0,0 +1,37 @@
+use std::fmt::{Display, Formatter};
+use crate::font::FontError;
+
+///Everything that can go wrong inside of the editor core.
+///None of these are fatal, the caller decides whether to report them or to recover (for example by a full repaint).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+	///Memory content and cursor position on the canvas do not match anymore. The canvas is likely showing garbage.
+	Corruption(&'static str),
+	///A letter was supposed to be drawn below the last line of the canvas.
+	OutOfCanvas {
+		x: usize,
+		y: usize,
+	},
+	///The symbol ID is not part of the font.
+	UnknownSymbol(u8),
+	Font(FontError),
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::Corruption(message) => write!(f, "VIOLATION/CORRUPTION: {}", message),
+			Error::OutOfCanvas { x, y } => write!(f, "VIOLATION: Tried to draw letter outside of the canvas at {}/{}. Before drawing a letter, make sure that there is a next line accessible when the cursor is at the end of a line!", x, y),
+			Error::UnknownSymbol(id) => write!(f, "VIOLATION: Tried to draw letter that does not exist with ID: {}", id),
+			Error::Font(error) => write!(f, "Could not load font: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<FontError> for Error {
+	fn from(error: FontError) -> Self {
+		Error::Font(error)
+	}
+}
@@ -1,19 +1,26 @@
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::fs::read_to_string;
 use std::path::Path;
+use crate::config::{REPLACEMENT, SPACE};
 
-//File name to parse:
-const FILE_NAME: &str = "FontBytes.txt";
-//Symbol keys of the entries in that file:
+//Default font, built into the binary so that it works from any directory:
+pub const DEFAULT_FONT: &str = include_str!("../FontBytes.txt");
+pub const DEFAULT_FONT_NAME: &str = "FontBytes.txt (built-in)";
+//Symbol keys of the entries in fonts without key lines, like the built-in one:
 const KEYS: &str = concat!(
 	"ABCDEFGHIJKLMNOPQRSTUVWXYZ",
 	"abcdefghijklmnopqrstuvwxyz",
 	"?!.,:'\"",
 	"0123456789",
 	"+-*/=()→←↓↑",
+	";#_[]{}<>&|%@$",
 );
 
-#[derive(Copy, Clone)]
+//The charset has 7 bits and IDs start at 1 after space. The replacement symbol and the newline in memory are above it:
+const MAX_SYMBOLS: usize = 0x7F;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Instruction {
 	pub clear: bool,
 	pub x_start: u32,
@@ -24,10 +31,129 @@ pub struct Instruction {
 	pub y_end: u32,
 }
 
+impl Instruction {
+	///Line of a font file, the reverse of parsing it. The start bit marks the first instruction of a symbol.
+	///Clearing the point 0/3 without start bit is all zeros, which the parser skips as empty line. Such an instruction cannot be stored.
+	pub fn encode(&self, is_start: bool) -> String {
+		let bit = |value: bool| if value { '1' } else { '0' };
+		//Y is stored with an offset of -3 as signed 4 bit value:
+		let y = |value: u32| format!("{:04b}", (value as i32 - 3) as u32 & 0b1111);
+		//Assembled with bit 0 first, the file has it last:
+		let reversed = format!(
+			"{}{:03b}{:03b}{}{}{}{}{}",
+			bit(!self.clear),
+			self.x_start,
+			self.x_end,
+			bit(self.x_fill),
+			y(self.y_start),
+			y(self.y_end),
+			bit(self.y_fill),
+			bit(is_start),
+		);
+		reversed.chars().rev().collect()
+	}
+}
+
+///Symbol in the font file format, with key line.
+pub fn format_symbol(key: char, symbol: &[Instruction]) -> String {
+	let mut text = format!("key {}\n", key);
+	for (index, instruction) in symbol.iter().enumerate() {
+		text.push_str(&instruction.encode(index == 0));
+		text.push('\n');
+	}
+	text
+}
+
 pub type SymbolDescription = Vec<Instruction>;
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FontError {
+	Read(String),
+	InvalidLine(String),
+	InvalidCharacter(String),
+	XOutOfBounds(u32),
+	YOutOfBounds(i32),
+	XFillWithoutRange,
+	YFillWithoutRange,
+	MissingStartBit,
+	WrongSymbolCount {
+		expected: usize,
+		actual: usize,
+	},
+	///Some symbols have a key line, but this one does not.
+	MissingKey,
+	DuplicateKey(char),
+	///Space is always the empty symbol with ID 0.
+	ReservedKey(char),
+	///A key line has to be followed by the first instruction of a symbol.
+	KeyWithoutSymbol(char),
+	TooManySymbols(usize),
+	///Any of the other errors, with the font file and line (if it is about a single line) it was found in.
+	InFile {
+		file: String,
+		line: Option<usize>,
+		error: Box<FontError>,
+	},
+}
+
+impl FontError {
+	///Few words about the error, short enough for the status line (the file name is already known there).
+	pub fn summary(&self) -> String {
+		match self {
+			FontError::InFile { line: Some(line), error, .. } => format!("Font L{}: {}", line, error.short()),
+			FontError::InFile { line: None, error, .. } => format!("Font: {}", error.short()),
+			_ => format!("Font: {}", self.short()),
+		}
+	}
+	
+	fn short(&self) -> String {
+		match self {
+			FontError::Read(_) => "cannot read file".to_string(),
+			FontError::InvalidLine(_) => "invalid line".to_string(),
+			FontError::InvalidCharacter(_) => "invalid character".to_string(),
+			FontError::XOutOfBounds(x) => format!("x {} out of bounds", x),
+			FontError::YOutOfBounds(y) => format!("y {} out of bounds", y),
+			FontError::XFillWithoutRange => "x fill without range".to_string(),
+			FontError::YFillWithoutRange => "y fill without range".to_string(),
+			FontError::MissingStartBit => "missing start bit".to_string(),
+			FontError::WrongSymbolCount { expected, actual } => format!("{} of {} symbols", actual, expected),
+			FontError::MissingKey => "missing key line".to_string(),
+			FontError::DuplicateKey(key) => format!("duplicate key '{}'", key),
+			FontError::ReservedKey(key) => format!("reserved key '{}'", key),
+			FontError::KeyWithoutSymbol(key) => format!("key '{}' without symbol", key),
+			FontError::TooManySymbols(count) => format!("{} symbols, max {}", count, MAX_SYMBOLS),
+			FontError::InFile { error, .. } => error.short(),
+		}
+	}
+}
+
+impl Display for FontError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FontError::Read(error) => write!(f, "Failed to read file: {}", error),
+			FontError::InvalidLine(line) => write!(f, "Font file has invalid line: '{}'", line),
+			FontError::InvalidCharacter(line) => write!(f, "Font file line contains invalid character: '{}'", line),
+			FontError::XOutOfBounds(x) => write!(f, "X value is out of bounds: {}", x),
+			FontError::YOutOfBounds(y) => write!(f, "Y value is out of bounds: {}", y),
+			FontError::XFillWithoutRange => write!(f, "For X_Fill, the X start and end values must differ."),
+			FontError::YFillWithoutRange => write!(f, "For Y_Fill, the Y start and end values must differ."),
+			FontError::MissingStartBit => write!(f, "Got draw instruction without ever getting a start bit."),
+			FontError::WrongSymbolCount { expected, actual } => write!(f, "Loaded wrong amount of symbols from file. Expected {} got {}. Without key lines, the symbols have to be in the built-in order.", expected, actual),
+			FontError::MissingKey => write!(f, "Symbol has no key line, either all or no symbols need one."),
+			FontError::DuplicateKey(key) => write!(f, "Key '{}' is used by more than one symbol.", key),
+			FontError::ReservedKey(key) => write!(f, "Key '{}' is reserved.", key),
+			FontError::KeyWithoutSymbol(key) => write!(f, "Key line '{}' is not followed by the start of a symbol.", key),
+			FontError::TooManySymbols(count) => write!(f, "Font has {} symbols, at most {} are possible.", count, MAX_SYMBOLS),
+			FontError::InFile { file, line: Some(line), error } => write!(f, "{}:{}: {}", file, line, error),
+			FontError::InFile { file, line: None, error } => write!(f, "{}: {}", file, error),
+		}
+	}
+}
+
 pub struct Font {
 	pub space: SymbolDescription,
+	///Hatched box, drawn for IDs which are not part of the font.
+	pub replacement: SymbolDescription,
 	pub symbols_by_key: HashMap<char, SymbolDescription>,
 	pub symbols_by_id: HashMap<u8, SymbolDescription>,
 	pub keys_by_id: HashMap<u8, char>,
@@ -35,12 +161,47 @@ pub struct Font {
 	pub highest_id: u8,
 }
 
-pub fn load_font() -> Font {
-	let symbol_instructions = load_instructions();
-	if symbol_instructions.len() != KEYS.chars().count() {
-		panic!("Loaded wrong amount of symbols from file. Expected {} got {}.", KEYS.len(), symbol_instructions.len());
+impl Font {
+	///ID of the letter, or the replacement ID if the font does not have it.
+	pub fn id(&self, key: char) -> u8 {
+		self.ids_by_key.get(&key).copied().unwrap_or(REPLACEMENT)
+	}
+	
+	///Instructions of the symbol, or of the replacement symbol for unknown IDs.
+	pub fn symbol(&self, id: u8) -> &SymbolDescription {
+		self.symbols_by_id.get(&id).unwrap_or(&self.replacement)
+	}
+	
+	///ID of the same letter in another font, as IDs depend on the order of the symbols in the font file.
+	///IDs which are not part of this font stay the replacement ID, check with missing_letter first that the other font has all letters.
+	pub fn translate_id(&self, id: u8, other: &Font) -> u8 {
+		self.keys_by_id.get(&id).map_or(REPLACEMENT, |key| other.id(*key))
 	}
 	
+	///First letter of the IDs (of this font) which the other font does not have, None if switching fonts keeps all of them.
+	pub fn missing_letter(&self, ids: &[u8], other: &Font) -> Option<char> {
+		ids.iter()
+			.filter_map(|id| self.keys_by_id.get(id))
+			.find(|key| !other.ids_by_key.contains_key(key))
+			.copied()
+	}
+}
+
+///Loads the font built into the binary.
+pub fn load_font() -> Result<Font, FontError> {
+	parse_font(DEFAULT_FONT, DEFAULT_FONT_NAME)
+}
+
+///Loads a font file in the same format as the built-in one.
+pub fn load_font_file(path: &Path) -> Result<Font, FontError> {
+	let text = read_to_string(path).map_err(|error| FontError::Read(format!("{}: {}", path.display(), error)))?;
+	parse_font(&text, &path.display().to_string())
+}
+
+///Parses the text of a font file, the file name is only used for error messages.
+pub fn parse_font(text: &str, file_name: &str) -> Result<Font, FontError> {
+	let symbols = parse_font_symbols(text, file_name)?;
+	
 	let space = vec!(Instruction {
 		clear: true,
 		x_start: 0,
@@ -51,18 +212,35 @@ pub fn load_font() -> Font {
 		y_end: 10,
 	});
 	
+	//Box with every other pixel cleared inside:
+	let replacement = [
+		(false, (0, 4, true), (3, 10, true)),
+		(true, (1, 3, false), (5, 7, false)),
+		(true, (1, 3, false), (9, 9, false)),
+		(true, (2, 2, false), (4, 6, false)),
+		(true, (2, 2, false), (8, 8, false)),
+	].into_iter().map(|(clear, (x_start, x_end, x_fill), (y_start, y_end, y_fill))| Instruction {
+		clear,
+		x_start,
+		x_fill,
+		x_end,
+		y_start,
+		y_fill,
+		y_end,
+	}).collect();
+	
 	let mut symbols_by_key = HashMap::new();
 	let mut symbols_by_id = HashMap::new();
 	let mut ids_by_key = HashMap::new();
 	let mut keys_by_id = HashMap::new();
 	
 	symbols_by_key.insert(' ', space.clone());
-	symbols_by_id.insert(0, space.clone());
-	keys_by_id.insert(0, ' ');
-	ids_by_key.insert(' ', 0);
+	symbols_by_id.insert(SPACE, space.clone());
+	keys_by_id.insert(SPACE, ' ');
+	ids_by_key.insert(' ', SPACE);
 	
 	let mut counter = 0;
-	for (key, symbol) in KEYS.chars().zip(symbol_instructions.into_iter()) {
+	for FontSymbol { key, instructions: symbol, .. } in symbols {
 		counter += 1;
 		symbols_by_key.insert(key, symbol.clone());
 		symbols_by_id.insert(counter, symbol);
@@ -70,24 +248,103 @@ pub fn load_font() -> Font {
 		ids_by_key.insert(key, counter);
 	}
 	
-	return Font {
+	Ok(Font {
 		space,
+		replacement,
 		symbols_by_key,
 		symbols_by_id,
 		ids_by_key,
 		keys_by_id,
 		highest_id: counter,
-	};
+	})
+}
+
+///Symbol of a font file, with the lines its instructions are in.
+pub struct FontSymbol {
+	pub key: char,
+	pub lines: Vec<usize>,
+	pub instructions: SymbolDescription,
 }
 
-fn load_instructions() -> Vec<SymbolDescription> {
-	let text = read_to_string(Path::new(FILE_NAME)).expect("Failed to read file.");
+///Symbols of a font file in the order of the file (which is the order of their IDs), without space.
+pub fn parse_font_symbols(text: &str, file_name: &str) -> Result<Vec<FontSymbol>, FontError> {
+	let in_file = |line: Option<usize>, error: FontError| FontError::InFile {
+		file: file_name.to_string(),
+		line,
+		error: Box::new(error),
+	};
+	let symbols = parse_symbols(text).map_err(|(line, error)| in_file(line, error))?;
+	if symbols.len() > MAX_SYMBOLS {
+		return Err(in_file(None, FontError::TooManySymbols(symbols.len())));
+	}
+	//Fonts either name every symbol, or use the built-in order:
+	let keys = if symbols.iter().all(|symbol| symbol.key.is_none()) {
+		if symbols.len() != KEYS.chars().count() {
+			return Err(in_file(None, FontError::WrongSymbolCount {
+				expected: KEYS.chars().count(),
+				actual: symbols.len(),
+			}));
+		}
+		KEYS.chars().collect::<Vec<char>>()
+	} else {
+		let mut keys = Vec::new();
+		for symbol in &symbols {
+			let key = symbol.key.ok_or_else(|| in_file(Some(symbol.lines[0]), FontError::MissingKey))?;
+			if keys.contains(&key) {
+				return Err(in_file(Some(symbol.lines[0]), FontError::DuplicateKey(key)));
+			}
+			keys.push(key);
+		}
+		keys
+	};
 	
+	Ok(keys.into_iter().zip(symbols).map(|(key, symbol)| FontSymbol {
+		key,
+		lines: symbol.lines,
+		instructions: symbol.instructions,
+	}).collect())
+}
+
+struct ParsedSymbol {
+	///From the key line before the symbol, if there is one.
+	key: Option<char>,
+	///Line of each instruction.
+	lines: Vec<usize>,
+	instructions: SymbolDescription,
+}
+
+///Besides the 18 bit instruction lines, a font file can contain:
+/// key X - Names the symbol starting with the next instruction.
+/// # ... - Comment, empty lines are ignored as well.
+///Errors come with the line number they occurred in, or none if they are about the whole file.
+fn parse_symbols(text: &str) -> Result<Vec<ParsedSymbol>, (Option<usize>, FontError)> {
 	let mut all_symbols = Vec::new();
-	let mut current_symbol = None;
-	for line in text.lines() {
-		if line.len() != 18 {
-			panic!("Font file has invalid line: '{}'", line);
+	let mut current_symbol: Option<ParsedSymbol> = None;
+	let mut pending_key: Option<(char, usize)> = None;
+	for (index, line) in text.lines().enumerate() {
+		let line_number = index + 1;
+		let at_line = |error: FontError| (Some(line_number), error);
+		//Tolerate files saved with Windows line endings:
+		let line = line.strip_suffix('\r').unwrap_or(line);
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		if let Some(key) = line.strip_prefix("key ") {
+			let mut chars = key.chars();
+			let (Some(key), None) = (chars.next(), chars.next()) else {
+				return Err(at_line(FontError::InvalidLine(line.to_string())));
+			};
+			if key == ' ' {
+				return Err(at_line(FontError::ReservedKey(key)));
+			}
+			if let Some((key, key_line)) = pending_key {
+				return Err((Some(key_line), FontError::KeyWithoutSymbol(key)));
+			}
+			pending_key = Some((key, line_number));
+			continue;
+		}
+		if line.chars().count() != 18 {
+			return Err(at_line(FontError::InvalidLine(line.to_string())));
 		}
 		let line = &line.chars().rev().collect::<String>()[..];
 		
@@ -95,27 +352,29 @@ fn load_instructions() -> Vec<SymbolDescription> {
 		let mut is_empty = true;
 		for c in chars.iter() {
 			if *c != '0' && *c != '1' {
-				panic!("Font file line contains invalid character: '{}'", line);
+				return Err(at_line(FontError::InvalidCharacter(line.to_string())));
 			}
 			if *c == '1' {
 				is_empty = false;
 			}
 		}
+		//Also the case for clearing the point 0/3 in the middle of a symbol, which can therefore not be used:
 		if is_empty {
 			continue;
 		}
 		
 		//Split/Parse line:
 		
+		let bits = |range: std::ops::Range<usize>| u32::from_str_radix(&line[range], 2).map_err(|_| at_line(FontError::InvalidCharacter(line.to_string())));
 		let is_enable = chars[0] == '1';
-		let x_start = u32::from_str_radix(&line[1..4], 2).expect("Wat?");
-		let x_end = u32::from_str_radix(&line[4..7], 2).expect("Wat?");
+		let x_start = bits(1..4)?;
+		let x_end = bits(4..7)?;
 		let x_fill = chars[7] == '1';
-		let mut y_start = i32::from_str_radix(&line[8..12], 2).expect("Wat?");
+		let mut y_start = bits(8..12)? as i32;
 		if y_start >= 0b1000 {
 			y_start |= 0xFFFFFFF0u32 as i32; //Sign extension
 		}
-		let mut y_end = i32::from_str_radix(&line[12..16], 2).expect("Wat?");
+		let mut y_end = bits(12..16)? as i32;
 		if y_end >= 0b1000 {
 			y_end |= 0xFFFFFFF0u32 as i32; //Sign extension
 		}
@@ -124,25 +383,22 @@ fn load_instructions() -> Vec<SymbolDescription> {
 		
 		//Check bounds:
 		if x_start > 4 {
-			panic!("X value is out of bounds: {}", x_start);
+			return Err(at_line(FontError::XOutOfBounds(x_start)));
 		}
 		if x_end > 4 {
-			panic!("X value is out of bounds: {}", x_end);
+			return Err(at_line(FontError::XOutOfBounds(x_end)));
 		}
-		if y_start > 7 || y_start < -3 {
-			panic!("Y value is out of bounds: {}", y_start);
+		if !(-3..=7).contains(&y_start) {
+			return Err(at_line(FontError::YOutOfBounds(y_start)));
 		}
-		if y_end > 7 || y_end < -3 {
-			panic!("Y value is out of bounds: {}", y_end);
-		}
-		if !is_enable {
-			panic!("Inverted text is not supported. Encountered enabled==false");
+		if !(-3..=7).contains(&y_end) {
+			return Err(at_line(FontError::YOutOfBounds(y_end)));
 		}
 		if x_fill && x_start == x_end {
-			panic!("For X_Fill, the X start and end values must differ.");
+			return Err(at_line(FontError::XFillWithoutRange));
 		}
 		if y_fill && y_start == y_end {
-			panic!("For Y_Fill, the X start and end values must differ.");
+			return Err(at_line(FontError::YFillWithoutRange));
 		}
 		
 		//Move offset to be positive:
@@ -151,17 +407,23 @@ fn load_instructions() -> Vec<SymbolDescription> {
 		
 		if is_start {
 			// println!();
-			if current_symbol.is_some() {
-				all_symbols.push(current_symbol.unwrap());
+			if let Some(symbol) = current_symbol {
+				all_symbols.push(symbol);
 			}
-			current_symbol = Some(Vec::new());
-		}
-		if current_symbol.is_none() {
-			panic!("Got draw instruction without ever getting a start bit.");
+			current_symbol = Some(ParsedSymbol {
+				key: pending_key.take().map(|(key, _)| key),
+				lines: Vec::new(),
+				instructions: Vec::new(),
+			});
+		} else if let Some((key, key_line)) = pending_key {
+			return Err((Some(key_line), FontError::KeyWithoutSymbol(key)));
 		}
 		// println!("{} {} {} {} {} {}", x_start, x_end, x_fill, y_start, y_end, y_fill);
-		current_symbol.as_mut().unwrap().push(Instruction {
-			clear: false,
+		let symbol = current_symbol.as_mut().ok_or(at_line(FontError::MissingStartBit))?;
+		symbol.lines.push(line_number);
+		symbol.instructions.push(Instruction {
+			//Disabled instructions draw the background, to carve out of what was drawn before:
+			clear: !is_enable,
 			x_start,
 			x_fill,
 			x_end,
@@ -170,6 +432,9 @@ fn load_instructions() -> Vec<SymbolDescription> {
 			y_end,
 		});
 	}
-	all_symbols.push(current_symbol.unwrap());
-	return all_symbols;
+	if let Some((key, key_line)) = pending_key {
+		return Err((Some(key_line), FontError::KeyWithoutSymbol(key)));
+	}
+	all_symbols.push(current_symbol.ok_or((None, FontError::MissingStartBit))?);
+	Ok(all_symbols)
 }
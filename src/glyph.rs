@@ -0,0 +1,63 @@
+use crate::font::Instruction;
+
+//Area a symbol draws into, inside of its slot (which has one more empty pixel column and row):
+pub const GLYPH_WIDTH: usize = 5;
+pub const GLYPH_HEIGHT: usize = 11;
+
+///Pixels of one symbol, rows from top to bottom. True where the foreground color is drawn.
+pub type Bitmap = [[bool; GLYPH_WIDTH]; GLYPH_HEIGHT];
+
+///Rectangle of glyph pixels, Y counts from the top row of the glyph like on the canvas (and can be outside of the glyph).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Area {
+	pub x: i32,
+	pub y: i32,
+	pub width: u32,
+	pub height: u32,
+}
+
+///Rectangles one instruction draws (or clears), used to draw letters directly onto a canvas.
+///Like in rasterize, filled ranges go from the lower to the higher value, so start and end may be in any order.
+pub fn areas(operation: &Instruction) -> Vec<Area> {
+	//Filled ranges are a single span, otherwise only start and end are drawn: Points, lines or rectangles.
+	let spans = |fill: bool, start: u32, end: u32| if fill || start == end { vec![(start.min(end), start.max(end))] } else { vec![(start, start), (end, end)] };
+	let mut areas = Vec::new();
+	for (y_low, y_high) in spans(operation.y_fill, operation.y_start, operation.y_end) {
+		for (x_low, x_high) in spans(operation.x_fill, operation.x_start, operation.x_end) {
+			areas.push(Area {
+				x: x_low as i32,
+				//The top of the area is the row of the higher Y:
+				y: GLYPH_HEIGHT as i32 - 1 - y_high as i32,
+				width: x_high - x_low + 1,
+				height: y_high - y_low + 1,
+			});
+		}
+	}
+	areas
+}
+
+///Executes the drawing instructions of a symbol on an empty (background) bitmap, exactly like they are drawn onto the canvas.
+pub fn rasterize(symbol: &[Instruction]) -> Bitmap {
+	let mut bitmap = [[false; GLYPH_WIDTH]; GLYPH_HEIGHT];
+	for operation in symbol {
+		let value = !operation.clear;
+		let mut set = |x: u32, y: u32| {
+			//Y counts from the bottom:
+			if let Some(pixel) = GLYPH_HEIGHT.checked_sub(1 + y as usize)
+				.and_then(|row| bitmap.get_mut(row))
+				.and_then(|row| row.get_mut(x as usize)) {
+				*pixel = value;
+			}
+		};
+		//Filled ranges draw every pixel in between, otherwise only start and end: Points, lines or rectangles.
+		let range = |fill: bool, start: u32, end: u32| if fill { (start.min(end)..=start.max(end)).collect() } else { vec![start, end] };
+		let xs: Vec<u32> = range(operation.x_fill, operation.x_start, operation.x_end);
+		let ys: Vec<u32> = range(operation.y_fill, operation.y_start, operation.y_end);
+		for y in &ys {
+			for x in &xs {
+				set(*x, *y);
+			}
+		}
+	}
+	bitmap
+}
@@ -0,0 +1,61 @@
+use crate::config::GridLayout;
+use crate::font::Font;
+use crate::layout::{cursor_row, visual_rows, WrapMode};
+use crate::typewriter::{LetterInstruction, Typewriter};
+
+///Line numbers left of the text. Only the first row of a line gets a number, wrapped rows stay empty.
+///Instructions are in gutter coordinates, the text starts at the column after the gutter width of the window.
+///Remembers what is currently drawn, to only redraw the digits that changed (mostly after scrolling).
+#[derive(Default)]
+pub struct Gutter {
+	drawn: Vec<Vec<u8>>,
+	///Memory pointers, cursor row, wrap mode and grid of the last update. The numbers only change with them, so wakeups for blinking or font checks skip the layout.
+	updated_for: Option<(usize, usize, usize, WrapMode, GridLayout)>,
+}
+
+impl Gutter {
+	pub fn update(&mut self, typewriter: &Typewriter, font: &Font, gutter_instructions: &mut Vec<LetterInstruction>) {
+		let gutter_width = typewriter.grid.gutter_width;
+		if gutter_width == 0 {
+			return;
+		}
+		let state = (typewriter.memory.pointer_before_cursor, typewriter.memory.pointer_after_cursor, typewriter.cursor.y, typewriter.wrap_mode, typewriter.grid);
+		if self.updated_for == Some(state) {
+			return;
+		}
+		self.updated_for = Some(state);
+		//Starts out empty, like the canvas:
+		if self.drawn.len() != typewriter.grid.char_height || self.drawn.iter().any(|row| row.len() != gutter_width) {
+			self.drawn = vec![vec![0; gutter_width]; typewriter.grid.char_height];
+		}
+		for (y, number) in Self::line_numbers(typewriter).iter().enumerate() {
+			//Right aligned, with one empty column towards the text. Too long numbers only show their last digits:
+			let digits = number.map(|number| number.to_string()).unwrap_or_default();
+			let digits = &digits[digits.len().saturating_sub(gutter_width - 1)..];
+			let text = format!("{:>width$} ", digits, width = gutter_width - 1);
+			for (x, letter) in text.chars().enumerate() {
+				let id = font.ids_by_key.get(&letter).copied().unwrap_or(0);
+				if self.drawn[y][x] != id {
+					self.drawn[y][x] = id;
+					gutter_instructions.push(LetterInstruction {
+						pos_x: x,
+						pos_y: y,
+						id,
+					});
+				}
+			}
+		}
+	}
+	
+	///Line number (starting at 1) for each row of the canvas, None for wrapped rows and rows after the document.
+	pub fn line_numbers(typewriter: &Typewriter) -> Vec<Option<usize>> {
+		let rows = visual_rows(&typewriter.memory.contents(), typewriter.wrap_mode, typewriter.grid.char_width);
+		//The canvas is scrolled such that the cursor row is at the cursor Y position:
+		let top_row = cursor_row(&rows, typewriter.memory.pointer_before_cursor, typewriter.grid.char_width).saturating_sub(typewriter.cursor.y);
+		(0..typewriter.grid.char_height)
+			.map(|y| rows.get(top_row + y)
+				.filter(|row| !row.continuation)
+				.map(|row| row.line + 1))
+			.collect()
+	}
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::compiler::{compile_font, CompileError};
+use crate::font::Font;
+use crate::glyph::{Bitmap, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+//Imports bitmap fonts (BDF and PSF) by fitting their glyphs into the glyph of the display and compiling them into instructions.
+
+//Rows of the glyph on and above the baseline (y 0 to 7), the others are below (y -3 to -1):
+const GLYPH_ASCENT: usize = 8;
+const GLYPH_DESCENT: usize = GLYPH_HEIGHT - GLYPH_ASCENT;
+//Lower case letters are more likely to be missing, the baseline is guessed from the first of these which the font has:
+const BASELINE_KEYS: &str = "HEIL0";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ImportError {
+	///Line number and what is wrong with it.
+	InvalidBdf(usize, String),
+	InvalidPsf(String),
+	UnknownFormat,
+	Compile(CompileError),
+}
+
+impl Display for ImportError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ImportError::InvalidBdf(line, message) => write!(f, "Line {}: Invalid BDF font: {}", line, message),
+			ImportError::InvalidPsf(message) => write!(f, "Invalid PSF font: {}", message),
+			ImportError::UnknownFormat => write!(f, "Neither BDF nor PSF font"),
+			ImportError::Compile(error) => write!(f, "{}", error),
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SourceGlyph {
+	///Glyph name in BDF fonts, glyph number in PSF fonts.
+	pub name: String,
+	///Rows from top to bottom, all of them as big as the font.
+	pub pixels: Vec<Vec<bool>>,
+}
+
+///Bitmap font as read from the file, every glyph placed into the bounding box of the font.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SourceFont {
+	pub width: usize,
+	pub height: usize,
+	///Rows on and above the baseline.
+	pub ascent: usize,
+	pub glyphs: HashMap<char, SourceGlyph>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Fit {
+	///Glyphs bigger than the display glyph are scaled down, no pixel gets lost but strokes may merge.
+	Scale,
+	///Glyphs keep their size, what does not fit is cut off (centered horizontally, at the baseline vertically).
+	Crop,
+}
+
+///Reads BDF or PSF (version 1 or 2), depending on the content.
+pub fn parse_bitmap_font(bytes: &[u8]) -> Result<SourceFont, ImportError> {
+	if bytes.starts_with(&[0x36, 0x04]) || bytes.starts_with(&[0x72, 0xb5, 0x4a, 0x86]) {
+		parse_psf(bytes)
+	} else if bytes.starts_with(b"STARTFONT") {
+		parse_bdf(&String::from_utf8_lossy(bytes))
+	} else {
+		Err(ImportError::UnknownFormat)
+	}
+}
+
+///Glyph Bitmap Distribution Format, the text format of X11 fonts. Glyphs with an encoding of -1 are skipped.
+pub fn parse_bdf(text: &str) -> Result<SourceFont, ImportError> {
+	//Bounding box of the font: Width, height and offset of its lower left corner from the origin:
+	let mut bounding_box = None;
+	let mut glyphs = HashMap::new();
+	//Name, encoding and bounding box of the current glyph:
+	let mut glyph: Option<(String, Option<char>, Option<[i32; 4]>)> = None;
+	let mut lines = text.lines().enumerate();
+	while let Some((index, line)) = lines.next() {
+		let invalid = |message: &str| ImportError::InvalidBdf(index + 1, message.to_string());
+		let mut words = line.split_whitespace();
+		let numbers = |words: std::str::SplitWhitespace| words.map(str::parse::<i32>).collect::<Result<Vec<i32>, _>>().ok();
+		match words.next() {
+			Some("FONTBOUNDINGBOX") => {
+				let Some([width, height, x, y]) = numbers(words).and_then(|numbers| <[i32; 4]>::try_from(numbers).ok()) else {
+					return Err(invalid("FONTBOUNDINGBOX needs width, height and offset"));
+				};
+				if width <= 0 || height <= 0 || y > 0 || -y > height {
+					return Err(invalid("FONTBOUNDINGBOX has to be above and below the baseline"));
+				}
+				bounding_box = Some([width, height, x, y]);
+			}
+			Some("STARTCHAR") => glyph = Some((words.collect::<Vec<&str>>().join(" "), None, None)),
+			Some("ENCODING") => {
+				let (_, encoding, _) = glyph.as_mut().ok_or_else(|| invalid("ENCODING outside of a glyph"))?;
+				let code = numbers(words).and_then(|numbers| numbers.first().copied()).ok_or_else(|| invalid("ENCODING needs a number"))?;
+				*encoding = u32::try_from(code).ok().and_then(char::from_u32);
+			}
+			Some("BBX") => {
+				let (_, _, bbx) = glyph.as_mut().ok_or_else(|| invalid("BBX outside of a glyph"))?;
+				let numbers = numbers(words).and_then(|numbers| <[i32; 4]>::try_from(numbers).ok()).ok_or_else(|| invalid("BBX needs width, height and offset"))?;
+				if numbers[0] < 0 || numbers[1] < 0 {
+					return Err(invalid("BBX has a negative size"));
+				}
+				*bbx = Some(numbers);
+			}
+			Some("BITMAP") => {
+				let (name, encoding, bbx) = glyph.take().ok_or_else(|| invalid("BITMAP outside of a glyph"))?;
+				let [font_width, font_height, font_x, font_y] = bounding_box.ok_or_else(|| invalid("BITMAP before FONTBOUNDINGBOX"))?;
+				let [width, height, x, y] = bbx.ok_or_else(|| invalid("BITMAP without BBX"))?;
+				let ascent = font_height + font_y;
+				let mut pixels = vec![vec![false; font_width as usize]; font_height as usize];
+				for row in 0..height {
+					let (index, line) = lines.next().ok_or_else(|| invalid("BITMAP ends early"))?;
+					let line = line.trim();
+					if !line.is_ascii() || line.len() < (width as usize).div_ceil(8) * 2 {
+						return Err(ImportError::InvalidBdf(index + 1, "BITMAP row is too short".to_string()));
+					}
+					let bytes = (0..line.len() / 2).map(|byte| u8::from_str_radix(&line[byte * 2..byte * 2 + 2], 16)).collect::<Result<Vec<u8>, _>>()
+						.map_err(|_| ImportError::InvalidBdf(index + 1, "BITMAP row is not hexadecimal".to_string()))?;
+					//Rows count downwards from the top of the glyph, which is height rows above its bottom offset:
+					let font_row = ascent - (y + height) + row;
+					for column in 0..width {
+						let font_column = x - font_x + column;
+						let set = bytes[column as usize / 8] & (0x80 >> (column % 8)) != 0;
+						//Pixels outside of the bounding box of the font are ignored:
+						if set && (0..font_height).contains(&font_row) && (0..font_width).contains(&font_column) {
+							pixels[font_row as usize][font_column as usize] = true;
+						}
+					}
+				}
+				if let Some(key) = encoding {
+					glyphs.insert(key, SourceGlyph {
+						name,
+						pixels,
+					});
+				}
+			}
+			_ => {}
+		}
+	}
+	let [width, height, _, y] = bounding_box.ok_or(ImportError::InvalidBdf(text.lines().count(), "FONTBOUNDINGBOX is missing".to_string()))?;
+	Ok(SourceFont {
+		width: width as usize,
+		height: height as usize,
+		ascent: (height + y) as usize,
+		glyphs,
+	})
+}
+
+///PC Screen Font, the binary format of Linux console fonts. Without unicode table glyph numbers are used as character codes.
+///The format has no baseline, it is guessed from the bottom of the capital letters.
+pub fn parse_psf(bytes: &[u8]) -> Result<SourceFont, ImportError> {
+	let invalid = |message: &str| ImportError::InvalidPsf(message.to_string());
+	let u32_at = |offset: usize| bytes.get(offset..offset + 4).map(|value| u32::from_le_bytes(value.try_into().unwrap()) as usize);
+	//Offset, number, size and dimension of the glyphs, and the offset of the unicode table:
+	let (offset, count, glyph_size, width, height, table) = if bytes.starts_with(&[0x36, 0x04]) {
+		let (mode, size) = (*bytes.get(2).ok_or_else(|| invalid("header is too short"))?, *bytes.get(3).ok_or_else(|| invalid("header is too short"))? as usize);
+		let count = if mode & 0x01 != 0 { 512 } else { 256 };
+		(4, count, size, 8, size, (mode & 0x06 != 0).then_some(4 + count * size))
+	} else {
+		let header = (0..6).map(|field| u32_at(8 + field * 4)).collect::<Option<Vec<usize>>>().ok_or_else(|| invalid("header is too short"))?;
+		let [header_size, flags, count, glyph_size, height, width] = header[..].try_into().unwrap();
+		if glyph_size != height * width.div_ceil(8) {
+			return Err(invalid("glyph size does not match width and height"));
+		}
+		(header_size, count, glyph_size, width, height, (flags & 0x01 != 0).then_some(header_size.saturating_add(count.saturating_mul(glyph_size))))
+	};
+	if width == 0 || height == 0 || count.checked_mul(glyph_size).and_then(|size| size.checked_add(offset)).is_none_or(|end| bytes.len() < end) {
+		return Err(invalid("glyph data is too short"));
+	}
+	
+	//Characters of each glyph:
+	let mut keys: Vec<Vec<char>> = vec![Vec::new(); count];
+	match table {
+		//PSF 1 stores UCS-2 values, PSF 2 UTF-8. Each glyph ends with a terminator, sequences of several characters (after a separator) are skipped:
+		Some(table) if bytes.starts_with(&[0x36, 0x04]) => {
+			let mut values = bytes[table.min(bytes.len())..].chunks_exact(2).map(|value| u16::from_le_bytes([value[0], value[1]]));
+			for glyph_keys in keys.iter_mut() {
+				let mut sequence = false;
+				for value in values.by_ref().take_while(|value| *value != 0xFFFF) {
+					sequence |= value == 0xFFFE;
+					if let (false, Some(key)) = (sequence, char::from_u32(value as u32)) {
+						glyph_keys.push(key);
+					}
+				}
+			}
+		}
+		Some(table) => {
+			let mut entries = bytes[table.min(bytes.len())..].split(|byte| *byte == 0xFF);
+			for glyph_keys in keys.iter_mut() {
+				let entry = entries.next().unwrap_or(&[]);
+				let single = entry.split(|byte| *byte == 0xFE).next().unwrap_or(&[]);
+				glyph_keys.extend(String::from_utf8_lossy(single).chars().filter(|key| *key != char::REPLACEMENT_CHARACTER));
+			}
+		}
+		None => {
+			for (glyph, glyph_keys) in keys.iter_mut().enumerate() {
+				glyph_keys.extend(char::from_u32(glyph as u32));
+			}
+		}
+	}
+	
+	let row_bytes = width.div_ceil(8);
+	let mut glyphs = HashMap::new();
+	for (glyph, glyph_keys) in keys.iter().enumerate() {
+		let data = &bytes[offset + glyph * glyph_size..][..glyph_size];
+		let pixels = (0..height).map(|y| (0..width).map(|x| data[y * row_bytes + x / 8] & (0x80 >> (x % 8)) != 0).collect()).collect::<Vec<Vec<bool>>>();
+		for key in glyph_keys {
+			//The first glyph of a character wins:
+			glyphs.entry(*key).or_insert_with(|| SourceGlyph {
+				name: format!("glyph {}", glyph),
+				pixels: pixels.clone(),
+			});
+		}
+	}
+	
+	let ascent = BASELINE_KEYS.chars()
+		.filter_map(|key| glyphs.get(&key))
+		.find_map(|glyph: &SourceGlyph| glyph.pixels.iter().rposition(|row| row.contains(&true)))
+		.map(|bottom| bottom + 1)
+		.unwrap_or(height - height / 4);
+	Ok(SourceFont {
+		width,
+		height,
+		ascent,
+		glyphs,
+	})
+}
+
+///Places the glyphs of the keys into the display glyph, with the baselines aligned. Keys the source font does not have, and glyphs which end up empty, are left out.
+///Empty rows and columns around all glyphs are removed first, so that only what is left has to be scaled or cropped.
+pub fn fit_glyphs(source: &SourceFont, keys: &[char], fit: Fit) -> Vec<(char, Bitmap)> {
+	let glyphs = keys.iter().filter_map(|key| source.glyphs.get(key).map(|glyph| (*key, glyph))).collect::<Vec<(char, &SourceGlyph)>>();
+	let drawn = |x: usize, y: usize| glyphs.iter().any(|(_, glyph)| glyph.pixels[y][x]);
+	let Some(left) = (0..source.width).find(|x| (0..source.height).any(|y| drawn(*x, y))) else {
+		return Vec::new();
+	};
+	let right = (0..source.width).rfind(|x| (0..source.height).any(|y| drawn(*x, y))).unwrap();
+	let top = (0..source.height).find(|y| (0..source.width).any(|x| drawn(x, *y))).unwrap();
+	let bottom = (0..source.height).rfind(|y| (0..source.width).any(|x| drawn(x, *y))).unwrap();
+	//Used columns, rows above (and on) the baseline and rows below it:
+	let width = right + 1 - left;
+	let ascent = source.ascent.saturating_sub(top);
+	let descent = (bottom + 1).saturating_sub(source.ascent);
+	
+	//Position in the display glyph, of a position counted from the first used column or from the baseline, None if it is cut off:
+	let place = |position: usize, used: usize, available: usize| match fit {
+		_ if used <= available => Some(position),
+		Fit::Scale => Some(position * available / used),
+		Fit::Crop => Some(position).filter(|position| *position < available),
+	};
+	let column_offset = match fit {
+		Fit::Crop if width > GLYPH_WIDTH => -((width - GLYPH_WIDTH) as isize / 2),
+		_ => GLYPH_WIDTH.saturating_sub(width) as isize / 2,
+	};
+	
+	glyphs.into_iter().filter_map(|(key, glyph)| {
+		let mut bitmap = [[false; GLYPH_WIDTH]; GLYPH_HEIGHT];
+		//Empty rows can be outside of the used rows:
+		for (y, row) in glyph.pixels.iter().enumerate().filter(|(_, row)| row.contains(&true)) {
+			let bitmap_row = if y < source.ascent {
+				place(source.ascent - 1 - y, ascent, GLYPH_ASCENT).map(|row| GLYPH_ASCENT - 1 - row)
+			} else {
+				place(y - source.ascent, descent, GLYPH_DESCENT).map(|row| GLYPH_ASCENT + row)
+			};
+			for (x, pixel) in row.iter().enumerate() {
+				if !*pixel {
+					continue;
+				}
+				let column = match fit {
+					Fit::Scale => place(x - left, width, GLYPH_WIDTH).map(|column| column as isize + column_offset),
+					Fit::Crop => Some((x - left) as isize + column_offset),
+				};
+				if let (Some(bitmap_row), Some(column)) = (bitmap_row, column.filter(|column| (0..GLYPH_WIDTH as isize).contains(column))) {
+					bitmap[bitmap_row][column as usize] = true;
+				}
+			}
+		}
+		//Space is built-in, empty glyphs cannot be compiled:
+		(bitmap.iter().flatten().any(|pixel| *pixel)).then_some((key, bitmap))
+	}).collect()
+}
+
+///Font file with key lines for all symbols of the font which the source font has, starting with a comment header.
+///The header names the source glyph of each key, and lists the keys which are drawn as replacement symbol.
+pub fn import_font(source: &SourceFont, file_name: &str, font: &Font, fit: Fit) -> Result<String, ImportError> {
+	let keys = (1..=font.highest_id).map(|id| font.keys_by_id[&id]).collect::<Vec<char>>();
+	let glyphs = fit_glyphs(source, &keys, fit);
+	let text = compile_font(&glyphs).map_err(ImportError::Compile)?;
+	
+	let mut header = format!(
+		"# Imported from {}, {}x{} pixels, fit by {}\n",
+		file_name,
+		source.width,
+		source.height,
+		match fit {
+			Fit::Scale => "scaling",
+			Fit::Crop => "cropping",
+		},
+	);
+	for (key, _) in &glyphs {
+		header.push_str(&format!("# {} = U+{:04X} {}\n", key, *key as u32, source.glyphs[key].name));
+	}
+	let missing = keys.iter().filter(|key| !glyphs.iter().any(|(other, _)| other == *key)).collect::<String>();
+	if !missing.is_empty() {
+		header.push_str(&format!("# Missing: {}\n", missing));
+	}
+	Ok(header + &text)
+}
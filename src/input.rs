@@ -0,0 +1,49 @@
+//Keyboard input as understood by the typewriter, independent of any windowing library.
+//Keys are physical keys on a US layout, the typewriter decides which symbol they produce.
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Key {
+	///Letter key, identified by its lowercase letter 'a' to 'z'.
+	Letter(char),
+	///Digit key of the top row, 0 to 9.
+	Digit(u8),
+	Space,
+	Comma,
+	Period,
+	Slash,
+	Minus,
+	Backslash,
+	RightBracket,
+	///Additional key between left shift and Z on ISO keyboards.
+	NonUsBackslash,
+	Backspace,
+	Return,
+	Left,
+	Right,
+	Up,
+	Down,
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct KeyModifiers {
+	pub shift: bool,
+	pub control: bool,
+	///Left Alt, reserved for shortcuts.
+	pub alt: bool,
+	///Right Alt, which types the third symbol of a key.
+	pub alt_gr: bool,
+}
+
+impl KeyModifiers {
+	pub const NONE: KeyModifiers = KeyModifiers {
+		shift: false,
+		control: false,
+		alt: false,
+		alt_gr: false,
+	};
+	
+	///AltGr arrives as its own modifier, or as Control with Alt on some systems.
+	pub fn is_alt_gr(&self) -> bool {
+		self.alt_gr || (self.control && self.alt)
+	}
+}
@@ -0,0 +1,106 @@
+use crate::config::{NEWLINE, SPACE};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WrapMode {
+	///Lines longer than the canvas continue in the next row.
+	Wrap,
+	///Every line is one row, the canvas scrolls horizontally to follow the cursor.
+	NoWrap,
+	///Like wrap, but lines are broken after the last space that fits into the row. Words longer than a row are still split.
+	WordWrap,
+}
+
+impl WrapMode {
+	pub fn name(&self) -> &'static str {
+		match self {
+			WrapMode::Wrap => "WRAP",
+			WrapMode::NoWrap => "NOWRAP",
+			WrapMode::WordWrap => "WORDWRAP",
+		}
+	}
+	
+	///The mode to switch to, when cycling through all modes.
+	pub fn next(&self) -> WrapMode {
+		match self {
+			WrapMode::Wrap => WrapMode::WordWrap,
+			WrapMode::WordWrap => WrapMode::NoWrap,
+			WrapMode::NoWrap => WrapMode::Wrap,
+		}
+	}
+}
+
+///One row of the canvas, as the document would be drawn if the canvas was endless.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct VisualRow {
+	///Index of the first symbol of this row in the document.
+	pub start: usize,
+	///Logical line (counted by newlines, starting at 0), which this row is part of.
+	pub line: usize,
+	///True if this row is a wrapped continuation of the row before.
+	pub continuation: bool,
+}
+
+///Splits the document into the rows it occupies on a canvas of the given width. There is always at least one row.
+pub fn visual_rows(contents: &[u8], wrap_mode: WrapMode, width: usize) -> Vec<VisualRow> {
+	let mut rows = Vec::new();
+	let mut line_start = 0;
+	for (line, text) in contents.split(|value| *value == NEWLINE).enumerate() {
+		for row_start in line_rows(text, wrap_mode, width) {
+			rows.push(VisualRow {
+				start: line_start + row_start,
+				line,
+				continuation: row_start != 0,
+			});
+		}
+		line_start += text.len() + 1; //Skip the newline.
+	}
+	rows
+}
+
+///Where the rows of one logical line (without newline) start. The first row always starts at 0.
+pub fn line_rows(line: &[u8], wrap_mode: WrapMode, width: usize) -> Vec<usize> {
+	let mut starts = vec![0];
+	if wrap_mode == WrapMode::NoWrap {
+		return starts;
+	}
+	let mut start = 0;
+	//A row may be completely filled, only the letter after that starts a new row:
+	while line.len() - start > width {
+		start = match wrap_mode {
+			//A space right after a full row is not drawn, the next row starts after it:
+			WrapMode::WordWrap if line[start + width] == SPACE => start + width + 1,
+			WrapMode::WordWrap => line[start..start + width].iter()
+				.rposition(|value| *value == SPACE)
+				.map(|space| start + space + 1)
+				.unwrap_or(start + width),
+			_ => start + width,
+		};
+		starts.push(start);
+	}
+	starts
+}
+
+///Cursor X position behind the last symbol of a logical line (without newline).
+pub fn line_end_column(line: &[u8], wrap_mode: WrapMode, width: usize) -> usize {
+	line.len() - line_rows(line, wrap_mode, width).last().copied().unwrap_or(0)
+}
+
+///Index of the row containing the cursor. The cursor stays at the end of a full row, instead of jumping into the continuation row.
+///Only behind a space which did not fit into the row anymore, the cursor is at the start of the continuation row.
+pub fn cursor_row(rows: &[VisualRow], cursor: usize, width: usize) -> usize {
+	let row = rows.iter()
+		.rposition(|row| row.start < cursor || (row.start == cursor && !row.continuation))
+		.unwrap_or(0);
+	if cursor - rows[row].start > width && rows.get(row + 1).is_some_and(|next| next.continuation) {
+		row + 1
+	} else {
+		row
+	}
+}
+
+///Logical line and column of the cursor (both starting at 0), given the document part before the cursor.
+pub fn line_and_column(before_cursor: &[u8]) -> (usize, usize) {
+	let line = before_cursor.iter().filter(|value| **value == NEWLINE).count();
+	let column = before_cursor.iter().rev().take_while(|value| **value != NEWLINE).count();
+	(line, column)
+}
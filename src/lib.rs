@@ -2,4 +2,22 @@ pub mod config;
 pub mod font;
 pub mod typewriter;
 pub mod memory;
-pub mod cursor;
\ No newline at end of file
+pub mod cursor;
+pub mod input;
+pub mod error;
+pub mod screen;
+pub mod status;
+pub mod layout;
+pub mod gutter;
+pub mod replay;
+pub mod theme;
+pub mod clock;
+pub mod glyph;
+pub mod coalesce;
+pub mod compiler;
+pub mod preview;
+pub mod lint;
+pub mod watch;
+pub mod import;
+#[cfg(feature = "sdl2")]
+pub mod render;
\ No newline at end of file
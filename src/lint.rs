@@ -0,0 +1,143 @@
+use std::fmt::{Display, Formatter};
+use crate::font::{FontError, FontSymbol};
+use crate::glyph::{rasterize, GLYPH_HEIGHT};
+
+//Checks fonts for instructions and symbols which load fine, but are likely mistakes or do not work on the hardware.
+
+//Default for how many instructions the display executes per symbol:
+pub const INSTRUCTION_BUDGET: usize = 8;
+//Symbols which are expected to draw below the baseline:
+const DESCENDERS: &str = "gjpqy,;_|";
+//Rows of the bitmap below the baseline:
+const DESCENDER_ROWS: usize = 3;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Severity {
+	///Works, but is most likely not intended.
+	Warning,
+	///Draws wrongly or does not work at all.
+	Error,
+}
+
+impl Severity {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Finding {
+	///Line in the font file, None if the finding is about the whole file.
+	pub line: Option<usize>,
+	pub key: Option<char>,
+	///Short name of the check, stable for tools filtering the output.
+	pub rule: &'static str,
+	pub severity: Severity,
+	pub message: String,
+}
+
+impl Finding {
+	fn new(line: usize, key: char, rule: &'static str, severity: Severity, message: String) -> Self {
+		Self {
+			line: Some(line),
+			key: Some(key),
+			rule,
+			severity,
+			message,
+		}
+	}
+	
+	///The font could not be loaded at all, nothing else can be checked then.
+	pub fn from_load_error(error: &FontError) -> Self {
+		let (line, error) = match error {
+			FontError::InFile { line, error, .. } => (*line, error.as_ref()),
+			_ => (None, error),
+		};
+		Self {
+			line,
+			key: None,
+			rule: "load",
+			severity: Severity::Error,
+			message: error.to_string(),
+		}
+	}
+	
+	///One JSON object, for one finding per line.
+	pub fn to_json(&self, file: &str) -> String {
+		let optional = |value: Option<String>| value.unwrap_or_else(|| "null".to_string());
+		format!(
+			"{{\"file\":{},\"line\":{},\"key\":{},\"rule\":\"{}\",\"severity\":\"{}\",\"message\":{}}}",
+			json_string(file),
+			optional(self.line.map(|line| line.to_string())),
+			optional(self.key.map(|key| json_string(&key.to_string()))),
+			self.rule,
+			self.severity.name(),
+			json_string(&self.message),
+		)
+	}
+}
+
+impl Display for Finding {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		if let Some(line) = self.line {
+			write!(f, "{}: ", line)?;
+		}
+		write!(f, "{}[{}]", self.severity.name(), self.rule)?;
+		if let Some(key) = self.key {
+			write!(f, " '{}'", key)?;
+		}
+		write!(f, ": {}", self.message)
+	}
+}
+
+fn json_string(text: &str) -> String {
+	let mut json = String::from("\"");
+	for c in text.chars() {
+		match c {
+			'"' => json.push_str("\\\""),
+			'\\' => json.push_str("\\\\"),
+			'\n' => json.push_str("\\n"),
+			c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+			c => json.push(c),
+		}
+	}
+	json.push('"');
+	json
+}
+
+///Findings of all symbols, in the order of the file.
+pub fn lint_font(symbols: &[FontSymbol], budget: usize) -> Vec<Finding> {
+	let mut findings = Vec::new();
+	for (index, symbol) in symbols.iter().enumerate() {
+		let key = symbol.key;
+		let first_line = symbol.lines[0];
+		
+		for (number, (instruction, line)) in symbol.instructions.iter().zip(&symbol.lines).enumerate() {
+			//Filled ranges are drawn from the lower to the higher value, but the hardware might not be as forgiving:
+			if (instruction.x_fill && instruction.x_start > instruction.x_end) || (instruction.y_fill && instruction.y_start > instruction.y_end) {
+				findings.push(Finding::new(*line, key, "reversed", Severity::Warning, "Filled range has its start after its end, swap them to draw from low to high".to_string()));
+			}
+			if rasterize(&symbol.instructions[..number]) == rasterize(&symbol.instructions[..=number]) {
+				findings.push(Finding::new(*line, key, "redundant", Severity::Warning, "Instruction changes no pixel, everything it draws or clears is already covered by earlier ones".to_string()));
+			}
+		}
+		
+		if symbol.instructions.len() > budget {
+			findings.push(Finding::new(first_line, key, "budget", Severity::Error, format!("Symbol has {} instructions, the display only executes {}", symbol.instructions.len(), budget)));
+		}
+		
+		let bitmap = rasterize(&symbol.instructions);
+		if bitmap.iter().flatten().all(|pixel| !pixel) {
+			findings.push(Finding::new(first_line, key, "empty", Severity::Warning, "Symbol draws nothing, looks like space".to_string()));
+		} else if let Some(other) = symbols[..index].iter().find(|other| rasterize(&other.instructions) == bitmap) {
+			findings.push(Finding::new(first_line, key, "duplicate", Severity::Warning, format!("Symbol looks exactly like '{}'", other.key)));
+		}
+		if !DESCENDERS.contains(key) && bitmap[GLYPH_HEIGHT - DESCENDER_ROWS..].iter().flatten().any(|pixel| *pixel) {
+			findings.push(Finding::new(first_line, key, "descender", Severity::Warning, format!("Symbol draws below the baseline, which only '{}' are expected to", DESCENDERS)));
+		}
+	}
+	findings
+}
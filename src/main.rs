@@ -1,21 +1,46 @@
 extern crate sdl2;
 
-use std::time::Instant;
-use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod, Scancode};
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
-use sdl2::render::WindowCanvas;
-use typewriter::config::{HEIGHT, PIXEL_SIDE, TARGET_DISPLAY, WIDTH};
+use sdl2::render::{Texture, TextureCreator, WindowCanvas};
+use sdl2::video::{FullscreenType, WindowContext};
+use typewriter::clock::{Clock, SystemClock};
+use typewriter::coalesce::Coalescer;
+use typewriter::config::{GridLayout, Options, ResizeMode, REPLACEMENT, SLOT_HEIGHT, USAGE};
+use typewriter::cursor::{CursorBlink, CursorStyle};
+use typewriter::error::Error;
 use typewriter::font;
-use typewriter::font::Instruction;
+use typewriter::font::Font;
+use typewriter::gutter::Gutter;
+use typewriter::render::GlyphAtlas;
+use typewriter::status::StatusLine;
+use typewriter::theme;
+use typewriter::theme::Theme;
+use typewriter::input::{Key, KeyModifiers};
 use typewriter::typewriter::{LetterInstruction, Typewriter};
-
-const COLOR_BACKGROUND: Color = Color::RGB(10, 15, 10);
-const COLOR_FOREGROUND: Color = Color::RGB(50, 255, 50);
+use typewriter::watch::FontWatcher;
 
 pub fn main() -> Result<(), String> {
-	let font = font::load_font();
+	let options = Options::from_args(std::env::args().skip(1)).map_err(|error| format!("{}\n{}", error, USAGE))?;
+	let mut grid = options.grid;
+	let mut font = match &options.font_path {
+		Some(path) => font::load_font_file(path),
+		None => font::load_font(),
+	}.map_err(|error| error.to_string())?;
+	//A broken theme file is no reason to not start, it is reported in the status line instead:
+	let mut theme_error = None;
+	let themes = match &options.theme_path {
+		Some(path) => theme::load_theme_file(path).or_else(|error| {
+			println!("Could not load themes: {}", error);
+			theme_error = Some(error.summary());
+			theme::load_themes()
+		}),
+		None => theme::load_themes(),
+	}.map_err(|error| error.to_string())?;
+	let mut theme_index = 0;
+	let mut theme = &themes[theme_index];
 	
 	sdl2::hint::set("SDL_HINT_VIDEO_X11_NET_WM_BYPASS_COMPOSITOR", "0");
 	
@@ -24,21 +49,22 @@ pub fn main() -> Result<(), String> {
 	
 	let count = video_subsystem.num_video_displays().expect("Could not get amount of video displays.");
 	
-	const EFF_WIDTH: u32 = WIDTH * PIXEL_SIDE;
-	const EFF_HEIGHT: u32 = HEIGHT * PIXEL_SIDE;
+	let eff_width = grid.width() * grid.pixel_side;
+	let eff_height = grid.height() * grid.pixel_side;
 	let mut window_builder = video_subsystem
 		.window(
 			"Ecconia Maze Test",
-			EFF_WIDTH,
-			EFF_HEIGHT,
+			eff_width,
+			eff_height,
 		);
-	if count <= 1 || TARGET_DISPLAY > i32::MAX as u32 || TARGET_DISPLAY as i32 >= count {
+	window_builder.resizable();
+	if count <= 1 || grid.target_display > i32::MAX as u32 || grid.target_display as i32 >= count {
 		window_builder.position_centered();
 	} else {
-		let rect = video_subsystem.display_bounds(TARGET_DISPLAY as i32).expect("Could not get display bounds.");
+		let rect = video_subsystem.display_bounds(grid.target_display as i32).expect("Could not get display bounds.");
 		window_builder.position(
-			rect.x + (rect.w - EFF_WIDTH as i32) / 2,
-			rect.y + (rect.h - EFF_HEIGHT as i32) / 2
+			rect.x + (rect.w - eff_width as i32) / 2,
+			rect.y + (rect.h - eff_height as i32) / 2
 		);
 	}
 	let window = window_builder.build()
@@ -51,26 +77,60 @@ pub fn main() -> Result<(), String> {
 		.build()
 		.map_err(|e| e.to_string())?;
 	
-	canvas.set_scale(PIXEL_SIDE as f32, PIXEL_SIDE as f32).expect("Scale failed.");
+	//SDL scales the grid by the largest integer factor that fits into the window and centers it:
+	canvas.set_logical_size(grid.width(), grid.height()).map_err(|e| e.to_string())?;
+	canvas.set_integer_scale(true)?;
 	
-	canvas.set_draw_color(COLOR_BACKGROUND);
+	canvas.set_draw_color(sdl_color(theme.background));
 	canvas.clear();
 	canvas.present();
 	
 	let texture_creator = canvas.texture_creator();
-	let mut buffer_texture = texture_creator.create_texture_target(None, WIDTH, HEIGHT).map_err(|e| e.to_string())?;
-	canvas.with_texture_canvas(&mut buffer_texture, |buffer_canvas| {
-		buffer_canvas.set_draw_color(COLOR_BACKGROUND);
-		buffer_canvas.clear();
-	}).map_err(|e| e.to_string())?;
+	let mut buffer_texture = create_buffer(&mut canvas, &texture_creator, &grid, theme)?;
+	let mut atlas = create_atlas(&texture_creator, &font, theme)?;
 	
-	let mut typewriter = Typewriter::default();
+	let mut typewriter = Typewriter::new(grid);
+	let mut status_line = StatusLine::default();
+	let mut gutter = Gutter::default();
 	
 	let mut letter_instructions : Vec<LetterInstruction> = Vec::new();
-	let cursor_time = Instant::now();
+	let mut gutter_instructions : Vec<LetterInstruction> = Vec::new();
+	let mut coalescer = Coalescer::new(&grid);
+	let clock = SystemClock::default();
+	let mut blink = CursorBlink::new(options.blink_period);
+	//Only a font file can change, the built-in font cannot:
+	let mut font_watcher = options.font_path.as_ref().map(|path| FontWatcher::new(path, clock.now()));
+	if let Some(error) = theme_error {
+		status_line.show_message(error, clock.now());
+	}
 	let mut event_pump = sdl_context.event_pump()?;
+	//Only present a new frame when something visible changed:
+	let mut presented_cursor = None;
+	let mut window_changed = true;
+	let mut first_event = None;
 	'running: loop {
-		for event in event_pump.poll_iter() {
+		let mut new_grid = None;
+		let mut new_theme = None;
+		let mut new_font = None;
+		match font_watcher.as_mut().and_then(|watcher| watcher.poll(clock.now())) {
+			//Keep the old font if the new one has errors or lacks letters of the text, the full error is only printed as the status line is short:
+			Some(Ok(reloaded)) => match font.missing_letter(&typewriter.memory.contents(), &reloaded) {
+				Some(key) => {
+					println!("Could not reload font: It lacks '{}', which is used in the text.", key);
+					status_line.show_message(format!("Font lacks '{}'", key), clock.now());
+				}
+				None => {
+					status_line.clear_message();
+					new_font = Some(reloaded);
+				}
+			},
+			Some(Err(error)) => {
+				println!("Could not reload font: {}", error);
+				status_line.show_message(error.summary(), clock.now());
+			}
+			None => {}
+		}
+		for event in first_event.take().into_iter().chain(event_pump.poll_iter()) {
 			match event {
 				//Termination condition of the program:
 				Event::Quit { .. }
@@ -78,6 +138,60 @@ pub fn main() -> Result<(), String> {
 					keycode: Some(Keycode::Escape),
 					..
 				} => break 'running,
+				Event::KeyDown {
+					keycode: Some(Keycode::F11),
+					repeat: false,
+					..
+				} => {
+					let window = canvas.window_mut();
+					let fullscreen = if window.fullscreen_state() == FullscreenType::Off { FullscreenType::Desktop } else { FullscreenType::Off };
+					window.set_fullscreen(fullscreen)?;
+				}
+				//In letterbox mode SDL takes care of resizing:
+				Event::Window {
+					win_event: WindowEvent::SizeChanged(width, height),
+					..
+				} if grid.resize_mode == ResizeMode::Reflow => {
+					new_grid = Some(grid.reflowed(width as u32, height as u32));
+					//The grid might stay the same, but the resized window still has to be presented again:
+					window_changed = true;
+				}
+				//The window content might have been lost or scaled differently:
+				Event::Window { .. } => window_changed = true,
+				//Zoom with Control and +/-:
+				Event::KeyDown {
+					scancode: Some(scancode @ (Scancode::RightBracket | Scancode::Equals | Scancode::KpPlus | Scancode::Slash | Scancode::Minus | Scancode::KpMinus)),
+					repeat: false,
+					keymod,
+					..
+				} if translate_modifiers(keymod).control => {
+					let steps = if matches!(scancode, Scancode::RightBracket | Scancode::Equals | Scancode::KpPlus) { 1 } else { -1 };
+					let zoomed = grid.zoomed(steps);
+					let window = canvas.window_mut();
+					new_grid = Some(match grid.resize_mode {
+						ResizeMode::Letterbox => {
+							//The grid stays, but the window follows the pixel size (unless the screen is filled anyway):
+							if window.fullscreen_state() == FullscreenType::Off {
+								window.set_size(zoomed.width() * zoomed.pixel_side, zoomed.height() * zoomed.pixel_side).map_err(|e| e.to_string())?;
+							}
+							zoomed
+						}
+						ResizeMode::Reflow => {
+							let (width, height) = window.size();
+							zoomed.reflowed(width, height)
+						}
+					});
+				}
+				//Cycle through the themes with Control+T:
+				Event::KeyDown {
+					scancode: Some(Scancode::T),
+					repeat: false,
+					keymod,
+					..
+				} if translate_modifiers(keymod).control => {
+					theme_index = (theme_index + 1) % themes.len();
+					new_theme = Some(&themes[theme_index]);
+				}
 				//Custom keydown events to be considered by the typewriter:
 				Event::KeyDown {
 					scancode: Some(scancode),
@@ -85,151 +199,200 @@ pub fn main() -> Result<(), String> {
 					keymod,
 					..
 				} => {
-					typewriter.handle_input(&scancode, &keymod, &mut letter_instructions, &font);
+					if let Some(key) = translate_key(scancode) {
+						blink.reset(clock.now());
+						if let Err(error) = typewriter.handle_input(&key, &translate_modifiers(keymod), &mut letter_instructions, &font) {
+							//The canvas can no longer be trusted, redraw everything from memory:
+							println!("{}", error);
+							letter_instructions.clear();
+							if let Err(error) = typewriter.repaint(&mut letter_instructions, &font) {
+								println!("Repaint failed: {}", error);
+							}
+						}
+					}
 				}
 				_ => {}
 			}
 		}
 		
-		//Clear the canvas buffer:
-		canvas.set_draw_color(COLOR_BACKGROUND);
-		canvas.clear();
+		let new_grid = new_grid.filter(|new_grid| *new_grid != grid);
+		if new_grid.is_some() || new_theme.is_some() || new_font.is_some() {
+			//Start over with an empty buffer and draw everything again, laying out the whole document if the grid changed:
+			grid = new_grid.unwrap_or(grid);
+			theme = new_theme.unwrap_or(theme);
+			if let Some(new_font) = new_font {
+				//The memory stores IDs, which differ if symbols were added, removed or reordered (the new font has all letters of the text):
+				typewriter.memory.map_symbols(|id| font.translate_id(id, &new_font));
+				font = new_font;
+			}
+			canvas.set_logical_size(grid.width(), grid.height()).map_err(|e| e.to_string())?;
+			buffer_texture = create_buffer(&mut canvas, &texture_creator, &grid, theme)?;
+			atlas = create_atlas(&texture_creator, &font, theme)?;
+			status_line.reset();
+			gutter = Gutter::default();
+			coalescer.reset(&grid);
+			letter_instructions.clear();
+			let result = if new_grid.is_some() {
+				typewriter.set_grid(grid, &mut letter_instructions, &font)
+			} else {
+				typewriter.repaint(&mut letter_instructions, &font)
+			};
+			if let Err(error) = result {
+				println!("Repaint failed: {}", error);
+			}
+			window_changed = true;
+		}
 		
-		if !letter_instructions.is_empty() {
-			//Draw the new letters:
+		status_line.expire(clock.now());
+		status_line.update(&typewriter, &font, &mut letter_instructions);
+		gutter.update(&typewriter, &font, &mut gutter_instructions);
+		//Only draw the final letter of each slot, and only if it changed:
+		coalescer.coalesce(&mut letter_instructions);
+		
+		let buffer_changed = !letter_instructions.is_empty() || !gutter_instructions.is_empty();
+		if buffer_changed {
+			//Draw the new letters, the text is right of the gutter:
 			canvas.with_texture_canvas(&mut buffer_texture, |texture_canvas| {
-				for instruction in &letter_instructions {
-					let symbol = font.symbols_by_id.get(&instruction.id);
-					if symbol.is_none() {
-						println!("VIOLATION: Tried to draw letter that does not exist with ID: {}", instruction.id);
-						continue;
+				let status_row = grid.status_row();
+				let all_instructions = gutter_instructions.iter().map(|instruction| (instruction, 0, PALETTE_GUTTER))
+					.chain(letter_instructions.iter().map(|instruction| (
+						instruction,
+						grid.gutter_width,
+						if instruction.pos_y == status_row { PALETTE_STATUS } else { PALETTE_TEXT },
+					)));
+				for (instruction, column_offset, palette) in all_instructions {
+					//Still drawn, as replacement symbol, so that the corruption is visible:
+					if !font.symbols_by_id.contains_key(&instruction.id) && instruction.id != REPLACEMENT {
+						println!("{}", Error::UnknownSymbol(instruction.id));
 					}
-					draw_letter(texture_canvas, symbol.unwrap(), instruction.pos_x, instruction.pos_y);
+					atlas.draw(texture_canvas, instruction.id, palette, instruction.pos_x + column_offset, instruction.pos_y).expect("Failed to draw letter.");
 				}
 			}).expect("Failed to edit buffer texture.");
 			//Clear the instructions
 			letter_instructions.clear();
+			gutter_instructions.clear();
 		}
-		//Always update the canvas with the buffer:
-		canvas.copy(&buffer_texture, None, Rect::new(0,0,WIDTH,HEIGHT)).map_err(|e| e.to_string())?;
-		
-		//Draw cursor:
-		if cursor_time.elapsed().as_millis() % 1000 >= 500 {
-			let (x, y) = typewriter.cursor.get_draw_cursor_position();
-			draw_cursor(&mut canvas, x, y);
+		let cursor_state = (
+			blink.is_visible(clock.now()),
+			typewriter.cursor.get_draw_cursor_position(),
+			typewriter.symbol_after_cursor(),
+		);
+		if buffer_changed || window_changed || presented_cursor != Some(cursor_state) {
+			//Clear the canvas and update it with the buffer:
+			canvas.set_draw_color(sdl_color(theme.background));
+			canvas.clear();
+			canvas.copy(&buffer_texture, None, Rect::new(0, 0, grid.width(), grid.height())).map_err(|e| e.to_string())?;
+			
+			//Draw cursor:
+			let (visible, (x, y), symbol_after_cursor) = cursor_state;
+			if visible {
+				//Behind a full row there is no slot, only the border is left for a bar:
+				let style = if x == grid.char_width { CursorStyle::Bar } else { options.cursor_style };
+				let (x_offset, y_offset, width, height) = style.rect(x + grid.gutter_width, y);
+				canvas.set_draw_color(sdl_color(theme.cursor));
+				canvas.fill_rect(Rect::new(x_offset, y_offset, width, height)).expect("Failed to fill rect.");
+				if style == CursorStyle::Block {
+					//The letter below the cursor is drawn inverted:
+					if let Some(id) = symbol_after_cursor {
+						atlas.draw(&mut canvas, id, PALETTE_CURSOR, x + grid.gutter_width, y)?;
+					}
+				}
+			}
+			
+			//Apply:
+			canvas.present();
+			presented_cursor = Some(cursor_state);
+			window_changed = false;
 		}
 		
-		//Apply:
-		canvas.present();
+		//Sleep until the next event arrives, or until the cursor has to blink, the font file has to be checked or a message disappears:
+		let timeout = [
+			blink.until_change(clock.now()),
+			font_watcher.as_ref().map(|watcher| watcher.until_check(clock.now())),
+			status_line.until_expiry(clock.now()),
+		].into_iter().flatten().min();
+		first_event = match timeout {
+			Some(duration) => event_pump.wait_event_timeout(duration.as_micros().div_ceil(1000).max(1) as u32),
+			None => Some(event_pump.wait_event()),
+		};
 	}
 	
+	println!("{}", coalescer.stats);
 	Ok(())
 }
 
-fn draw_cursor(canvas: &mut WindowCanvas, x: usize, y: usize) {
-	canvas.set_draw_color(COLOR_FOREGROUND);
-	let x_offset = (x * 6) as i32;
-	let y_offset = (y * 12 + 11) as i32;
-	canvas.draw_line(
-		Point::new(x_offset, y_offset - 3),
-		Point::new(x_offset, y_offset - 10),
-	).expect("Failed to draw line.");
+///Creates the buffer holding all drawn letters, initially only containing the status line separator.
+fn create_buffer<'a>(canvas: &mut WindowCanvas, texture_creator: &'a TextureCreator<WindowContext>, grid: &GridLayout, theme: &Theme) -> Result<Texture<'a>, String> {
+	let mut buffer_texture = texture_creator.create_texture_target(None, grid.width(), grid.height()).map_err(|e| e.to_string())?;
+	canvas.with_texture_canvas(&mut buffer_texture, |buffer_canvas| {
+		buffer_canvas.set_draw_color(sdl_color(theme.background));
+		buffer_canvas.clear();
+		//Separate the status line from the text:
+		let separator_y = (grid.status_row() * SLOT_HEIGHT) as i32;
+		buffer_canvas.set_draw_color(sdl_color(theme.status));
+		buffer_canvas.draw_line(
+			Point::new(0, separator_y),
+			Point::new(grid.width() as i32 - 1, separator_y),
+		).expect("Failed to draw line.");
+	}).map_err(|e| e.to_string())?;
+	Ok(buffer_texture)
 }
 
-fn draw_letter(canvas: &mut WindowCanvas, symbol: &Vec<Instruction>, x: usize, y: usize) {
-	let x_offset = (x * 6 + 1) as i32;
-	let y_offset = (y * 12 + 11) as i32;
-	draw_letter_at(canvas, symbol, x_offset, y_offset);
+//Rows of the glyph atlas, one per color combination:
+const PALETTE_TEXT: usize = 0;
+const PALETTE_STATUS: usize = 1;
+const PALETTE_GUTTER: usize = 2;
+const PALETTE_CURSOR: usize = 3;
+
+fn create_atlas<'a>(texture_creator: &'a TextureCreator<WindowContext>, font: &Font, theme: &Theme) -> Result<GlyphAtlas<'a>, String> {
+	let background = sdl_color(theme.background);
+	GlyphAtlas::new(texture_creator, font, &[
+		(sdl_color(theme.text), background),
+		(sdl_color(theme.status), background),
+		(sdl_color(theme.gutter), background),
+		//Inverted, for the block cursor:
+		(background, sdl_color(theme.cursor)),
+	])
 }
 
-fn draw_letter_at(canvas: &mut WindowCanvas, symbol: &Vec<Instruction>, x_offset: i32, y_offset: i32) {
-	for operation in symbol.iter() {
-		let x_start = operation.x_start as i32;
-		let x_end = operation.x_end as i32;
-		let y_start = operation.y_start as i32;
-		let y_end = operation.y_end as i32;
-		
-		canvas.set_draw_color(if operation.clear { COLOR_BACKGROUND } else { COLOR_FOREGROUND });
-		
-		if operation.x_fill {
-			if operation.y_fill {
-				//Fill in both directions - fill a rect:
-				canvas.fill_rect(
-					Rect::new(
-						x_offset + x_start,
-						y_offset - y_start - 10,
-						(x_end - x_start + 1) as u32,
-						(y_end - y_start + 1) as u32,
-					)
-				).expect("Failed to fill rect.");
-			} else {
-				//Just X-Fill:
-				if y_start == y_end {
-					//One horizontal line:
-					canvas.draw_line(
-						Point::new(x_offset + x_start, y_offset - y_start),
-						Point::new(x_offset + x_end, y_offset - y_end),
-					).expect("Failed to draw line.");
-				} else {
-					//Two horizontal lines:
-					canvas.draw_line(
-						Point::new(x_offset + x_start, y_offset - y_start),
-						Point::new(x_offset + x_end, y_offset - y_start),
-					).expect("Failed to draw line.");
-					canvas.draw_line(
-						Point::new(x_offset + x_start, y_offset - y_end),
-						Point::new(x_offset + x_end, y_offset - y_end),
-					).expect("Failed to draw line.");
-				}
-			}
-		} else {
-			if operation.y_fill {
-				//Just Y-Fill:
-				if x_start == x_end {
-					//One vertical line:
-					canvas.draw_line(
-						Point::new(x_offset + x_start, y_offset - y_start),
-						Point::new(x_offset + x_end, y_offset - y_end),
-					).expect("Failed to draw line.");
-				} else {
-					//Two vertical lines:
-					canvas.draw_line(
-						Point::new(x_offset + x_start, y_offset - y_start),
-						Point::new(x_offset + x_start, y_offset - y_end),
-					).expect("Failed to draw line.");
-					canvas.draw_line(
-						Point::new(x_offset + x_end, y_offset - y_start),
-						Point::new(x_offset + x_end, y_offset - y_end),
-					).expect("Failed to draw line.");
-				}
-			} else {
-				//No fill at all, just draw the points:
-				if x_start == x_end && y_start == y_end {
-					canvas.draw_point(
-						Point::new(x_offset + x_start, y_offset - y_start),
-					).expect("Failed to draw point.");
-				} else if x_start == x_end || y_start == y_end {
-					canvas.draw_point(
-						Point::new(x_offset + x_start, y_offset - y_start),
-					).expect("Failed to draw point.");
-					canvas.draw_point(
-						Point::new(x_offset + x_end, y_offset - y_end),
-					).expect("Failed to draw point.");
-				} else {
-					canvas.draw_point(
-						Point::new(x_offset + x_start, y_offset - y_start),
-					).expect("Failed to draw point.");
-					canvas.draw_point(
-						Point::new(x_offset + x_end, y_offset - y_end),
-					).expect("Failed to draw point.");
-					canvas.draw_point(
-						Point::new(x_offset + x_start, y_offset - y_end),
-					).expect("Failed to draw point.");
-					canvas.draw_point(
-						Point::new(x_offset + x_end, y_offset - y_start),
-					).expect("Failed to draw point.");
-				}
-			}
-		}
+fn translate_key(scancode: Scancode) -> Option<Key> {
+	let raw_scancode = scancode as u32;
+	if raw_scancode >= Scancode::A as u32 && raw_scancode <= Scancode::Z as u32 {
+		return char::from_u32(raw_scancode - Scancode::A as u32 + 'a' as u32).map(Key::Letter);
 	}
+	if raw_scancode >= Scancode::Num1 as u32 && raw_scancode <= Scancode::Num9 as u32 {
+		return Some(Key::Digit((raw_scancode - Scancode::Num1 as u32 + 1) as u8));
+	}
+	match scancode {
+		Scancode::Num0 => Some(Key::Digit(0)),
+		Scancode::Space => Some(Key::Space),
+		Scancode::Comma => Some(Key::Comma),
+		Scancode::Period => Some(Key::Period),
+		Scancode::Slash => Some(Key::Slash),
+		Scancode::Minus => Some(Key::Minus),
+		Scancode::Backslash => Some(Key::Backslash),
+		Scancode::RightBracket => Some(Key::RightBracket),
+		Scancode::NonUsBackslash => Some(Key::NonUsBackslash),
+		Scancode::Backspace => Some(Key::Backspace),
+		Scancode::Return => Some(Key::Return),
+		Scancode::Left => Some(Key::Left),
+		Scancode::Right => Some(Key::Right),
+		Scancode::Up => Some(Key::Up),
+		Scancode::Down => Some(Key::Down),
+		_ => None,
+	}
+}
+
+fn translate_modifiers(keymod: Mod) -> KeyModifiers {
+	KeyModifiers {
+		shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+		control: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+		alt: keymod.intersects(Mod::LALTMOD),
+		//Depending on the system, AltGr is reported as right Alt or as mode switch:
+		alt_gr: keymod.intersects(Mod::RALTMOD | Mod::MODEMOD),
+	}
+}
+
+fn sdl_color(color: theme::Color) -> Color {
+	Color::RGB(color.r, color.g, color.b)
 }
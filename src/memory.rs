@@ -1,4 +1,4 @@
-use crate::config::MEMORY_SIZE;
+use crate::config::{MEMORY_SIZE, NEWLINE};
 
 pub struct Memory {
 	pub memory: [u8; MEMORY_SIZE],
@@ -35,6 +35,11 @@ impl Memory {
 		self.pointer_before_cursor == self.pointer_after_cursor + 1
 	}
 	
+	///Amount of symbols stored on both sides of the cursor.
+	pub fn used(&self) -> usize {
+		self.pointer_before_cursor + (self.memory.len() - 1 - self.pointer_after_cursor)
+	}
+	
 	pub fn cursor_at_end(&self) -> bool {
 		self.pointer_after_cursor == self.memory.len() - 1
 	}
@@ -52,4 +57,20 @@ impl Memory {
 		self.memory[self.pointer_before_cursor] = self.memory[self.pointer_after_cursor];
 		self.pointer_before_cursor += 1;
 	}
+	
+	///Replaces every stored symbol (but not the newlines), for example to translate them into the IDs of another font.
+	pub fn map_symbols(&mut self, mut map: impl FnMut(u8) -> u8) {
+		for index in (0..self.pointer_before_cursor).chain(self.pointer_after_cursor + 1..self.memory.len()) {
+			if self.memory[index] != NEWLINE {
+				self.memory[index] = map(self.memory[index]);
+			}
+		}
+	}
+	
+	///All symbols in document order, ignoring where the cursor is.
+	pub fn contents(&self) -> Vec<u8> {
+		let mut contents = self.memory[..self.pointer_before_cursor].to_vec();
+		contents.extend_from_slice(&self.memory[self.pointer_after_cursor + 1..]);
+		contents
+	}
 }
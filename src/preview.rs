@@ -0,0 +1,75 @@
+use crate::font::Font;
+use crate::glyph::{rasterize, Bitmap};
+
+//Prints fonts as ASCII art, in the format the font compiler reads. Information about each glyph is added as comment.
+
+fn row_text(row: &[bool]) -> String {
+	row.iter().map(|pixel| if *pixel { '#' } else { '.' }).collect()
+}
+
+///Keys of all symbols except space, ordered by ID.
+fn keys(font: &Font) -> Vec<char> {
+	let mut ids = font.keys_by_id.keys().copied().filter(|id| *id != 0).collect::<Vec<u8>>();
+	ids.sort_unstable();
+	ids.into_iter().map(|id| font.keys_by_id[&id]).collect()
+}
+
+fn bitmap(font: &Font, key: char) -> Option<Bitmap> {
+	font.symbols_by_key.get(&key).map(|symbol| rasterize(symbol))
+}
+
+///Every symbol with key, ID and instruction count.
+pub fn preview_font(font: &Font) -> String {
+	let mut text = String::new();
+	for key in keys(font) {
+		let symbol = &font.symbols_by_key[&key];
+		text.push_str(&format!("// ID {}, {} instructions\nkey {}\n", font.ids_by_key[&key], symbol.len(), key));
+		for row in rasterize(symbol) {
+			text.push_str(&row_text(&row));
+			text.push('\n');
+		}
+		text.push('\n');
+	}
+	text
+}
+
+///Symbols whose pixels differ, old and new next to each other with the changes: '+' drawn now, '-' not drawn anymore.
+///Symbols which only exist in one of the fonts are listed at the end. Empty if both fonts draw the same.
+pub fn diff_fonts(old: &Font, new: &Font) -> String {
+	let mut text = String::new();
+	let (old_keys, new_keys) = (keys(old), keys(new));
+	let mut changed = 0;
+	for key in old_keys.iter().filter(|key| new_keys.contains(key)) {
+		let (Some(before), Some(after)) = (bitmap(old, *key), bitmap(new, *key)) else {
+			continue;
+		};
+		if before == after {
+			continue;
+		}
+		changed += 1;
+		text.push_str(&format!("key {} ({} -> {} instructions)\n", key, old.symbols_by_key[key].len(), new.symbols_by_key[key].len()));
+		text.push_str("old   new   change\n");
+		for (row_before, row_after) in before.iter().zip(after.iter()) {
+			let change = row_before.iter().zip(row_after.iter()).map(|pixels| match pixels {
+				(false, true) => '+',
+				(true, false) => '-',
+				_ => ' ',
+			}).collect::<String>();
+			text.push_str(format!("{} {} {}", row_text(row_before), row_text(row_after), change).trim_end());
+			text.push('\n');
+		}
+		text.push('\n');
+	}
+	let removed = old_keys.iter().filter(|key| !new_keys.contains(key)).collect::<String>();
+	let added = new_keys.iter().filter(|key| !old_keys.contains(key)).collect::<String>();
+	if !removed.is_empty() {
+		text.push_str(&format!("Removed: {}\n", removed));
+	}
+	if !added.is_empty() {
+		text.push_str(&format!("Added: {}\n", added));
+	}
+	if !text.is_empty() {
+		text.push_str(&format!("{} changed, {} removed, {} added\n", changed, removed.chars().count(), added.chars().count()));
+	}
+	text
+}
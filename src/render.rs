@@ -0,0 +1,77 @@
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget, Texture, TextureCreator};
+use crate::config::{SLOT_HEIGHT, SLOT_WIDTH};
+use crate::font::{Font, Instruction};
+use crate::glyph::{areas, rasterize, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+//Drawing of symbols onto SDL canvases, only available with the sdl2 feature.
+
+///All symbols of a font rasterized once into a texture, one row of 6x12 slots per pair of foreground and background color.
+///Drawing a letter is then a single copy, which also overwrites whatever was drawn in that slot before.
+pub struct GlyphAtlas<'a> {
+	texture: Texture<'a>,
+	///Symbols above are drawn with the replacement symbol, which is in the column after it.
+	highest_id: u8,
+}
+
+impl<'a> GlyphAtlas<'a> {
+	pub fn new<T>(texture_creator: &'a TextureCreator<T>, font: &Font, palettes: &[(Color, Color)]) -> Result<Self, String> {
+		let columns = font.highest_id as usize + 2;
+		let (width, height) = (columns * SLOT_WIDTH, palettes.len().max(1) * SLOT_HEIGHT);
+		let mut pixels = vec![0; width * height * 3];
+		for (row, (color, background)) in palettes.iter().enumerate() {
+			for id in 0..columns {
+				//The last column is the replacement symbol:
+				let bitmap = rasterize(if id == columns - 1 { &font.replacement } else { font.symbol(id as u8) });
+				for y in 0..SLOT_HEIGHT {
+					for x in 0..SLOT_WIDTH {
+						//The glyph is drawn one pixel right and down of the slot corner:
+						let foreground = x >= 1 && y >= 1 && bitmap[y - 1].get(x - 1).copied().unwrap_or(false);
+						let pixel = if foreground { color } else { background };
+						let index = ((row * SLOT_HEIGHT + y) * width + id * SLOT_WIDTH + x) * 3;
+						pixels[index..index + 3].copy_from_slice(&[pixel.r, pixel.g, pixel.b]);
+					}
+				}
+			}
+		}
+		let mut texture = texture_creator.create_texture_static(PixelFormatEnum::RGB24, width as u32, height as u32).map_err(|e| e.to_string())?;
+		texture.update(None, &pixels, width * 3).map_err(|e| e.to_string())?;
+		Ok(Self {
+			texture,
+			highest_id: font.highest_id,
+		})
+	}
+	
+	///Draws the symbol in the colors of the palette into the slot at the grid position.
+	pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, id: u8, palette: usize, x: usize, y: usize) -> Result<(), String> {
+		//Only the glyph area is copied, the empty column and row of the slot may contain other things (like the status line separator):
+		let column = id.min(self.highest_id.saturating_add(1)) as usize;
+		let source = Rect::new((column * SLOT_WIDTH + 1) as i32, (palette * SLOT_HEIGHT + 1) as i32, GLYPH_WIDTH as u32, GLYPH_HEIGHT as u32);
+		let target = Rect::new((x * SLOT_WIDTH + 1) as i32, (y * SLOT_HEIGHT + 1) as i32, GLYPH_WIDTH as u32, GLYPH_HEIGHT as u32);
+		canvas.copy(&self.texture, source, target)
+	}
+}
+
+pub fn draw_letter<T: RenderTarget>(canvas: &mut Canvas<T>, symbol: &[Instruction], x: usize, y: usize, color: Color, background: Color) {
+	let x_offset = (x * SLOT_WIDTH + 1) as i32;
+	let y_offset = (y * SLOT_HEIGHT + 11) as i32;
+	draw_letter_at(canvas, symbol, x_offset, y_offset, color, background);
+}
+
+pub fn draw_letter_at<T: RenderTarget>(canvas: &mut Canvas<T>, symbol: &[Instruction], x_offset: i32, y_offset: i32, color: Color, background: Color) {
+	for operation in symbol.iter() {
+		canvas.set_draw_color(if operation.clear { background } else { color });
+		//The Y offset is the bottom row of the glyph:
+		for area in areas(operation) {
+			canvas.fill_rect(
+				Rect::new(
+					x_offset + area.x,
+					y_offset - (GLYPH_HEIGHT as i32 - 1) + area.y,
+					area.width,
+					area.height,
+				)
+			).expect("Failed to fill rect.");
+		}
+	}
+}
@@ -0,0 +1,340 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use crate::clock::{Clock, ManualClock};
+use crate::config::{GridLayout, BLINK_PERIOD_MS, NEWLINE};
+use crate::cursor::CursorBlink;
+use crate::error::Error;
+use crate::font::Font;
+use crate::gutter::Gutter;
+use crate::input::{Key, KeyModifiers};
+use crate::screen::Screen;
+use crate::status::StatusLine;
+use crate::typewriter::{LetterInstruction, Typewriter};
+
+//Replays keystroke scripts against a typewriter without any window. One command per line:
+// grid 20 4             Starts over with an empty typewriter, using a text grid of 20 columns and 4 rows.
+// resize 30 5           Changes the text grid of the typewriter, keeping the document, like when the window gets resized.
+// type "mov a, 5"       Types the letters, '\n' presses Return.
+// key Left x3           Presses a key, optionally repeated. Modifiers (Shift, Ctrl, Alt, AltGr) are prefixed: key Ctrl+Shift+w
+// expect-text "a\nb"    Compares the whole memory content (cursor position is ignored).
+// repaint               Redraws the whole canvas from memory.
+// expect-cursor 3 0     Compares the cursor position (x y).
+// expect-status "L1 C1"  Compares the status line, with consecutive spaces collapsed into one.
+// expect-gutter 4 - 5   Compares the line numbers of the rows, '-' for rows without number. Missing rows are expected without number.
+// blink 500             Sets the blink period in milliseconds, 'off' to not blink. Defaults to the configured period.
+// wait 250              Lets the time pass, which is otherwise frozen.
+// expect-cursor-visible yes  Compares whether the cursor is visible (yes or no). Every key press makes it visible.
+// expect-blink-in 250   Compares the milliseconds until the cursor appears or vanishes next, 'off' if it never does.
+// expect-screen         Compares the canvas, rows follow as lines starting with '|'.
+//                       Trailing whitespace is ignored and missing rows are expected to be empty.
+//Empty lines and lines starting with '#' are ignored.
+
+pub struct ReplayError {
+	pub line: usize,
+	pub message: String,
+}
+
+impl Display for ReplayError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Line {}: {}", self.line, self.message)
+	}
+}
+
+pub struct Replay<'a> {
+	pub typewriter: Typewriter,
+	pub screen: Screen,
+	pub status_line: StatusLine,
+	font: &'a Font,
+	letter_instructions: Vec<LetterInstruction>,
+	clock: ManualClock,
+	blink: CursorBlink,
+}
+
+impl<'a> Replay<'a> {
+	pub fn new(font: &'a Font) -> Self {
+		let mut replay = Self {
+			typewriter: Typewriter::default(),
+			screen: Screen::default(),
+			status_line: StatusLine::default(),
+			font,
+			letter_instructions: Vec::new(),
+			clock: ManualClock::default(),
+			blink: CursorBlink::new(Some(Duration::from_millis(BLINK_PERIOD_MS))),
+		};
+		//Draw the initial status line:
+		replay.flush(Ok(())).expect("Status line has to fit onto the canvas.");
+		replay
+	}
+	
+	///Runs all commands of the script, stops at the first divergence.
+	pub fn run(&mut self, script: &str) -> Result<(), ReplayError> {
+		let mut lines = script.lines().enumerate().peekable();
+		while let Some((index, line)) = lines.next() {
+			let line_number = index + 1;
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+			let argument = argument.trim();
+			let result = match command {
+				"grid" => parse_grid(argument).and_then(|grid| {
+					self.typewriter = Typewriter::new(grid);
+					self.clear_screen(Ok(()))
+				}),
+				"resize" => parse_grid(argument).and_then(|grid| {
+					let result = self.typewriter.set_grid(grid, &mut self.letter_instructions, self.font);
+					self.clear_screen(result)
+				}),
+				"type" => parse_string(argument).and_then(|text| self.type_text(&text)),
+				"key" => self.press_key(argument),
+				"repaint" => {
+					let result = self.typewriter.repaint(&mut self.letter_instructions, self.font);
+					self.flush(result)
+				}
+				"blink" => parse_milliseconds(argument).map(|period| {
+					self.blink = CursorBlink::new(period);
+					self.blink.reset(self.clock.now());
+				}),
+				"wait" => parse_milliseconds(argument).map(|duration| self.clock.advance(duration.unwrap_or_default())),
+				"expect-cursor-visible" => self.expect_cursor_visible(argument),
+				"expect-blink-in" => parse_milliseconds(argument).and_then(|expected| {
+					let actual = self.blink.until_change(self.clock.now());
+					if actual != expected {
+						return Err(format!("Cursor blinks in {:?} but expected {:?}", actual, expected));
+					}
+					Ok(())
+				}),
+				"expect-text" => parse_string(argument).and_then(|text| self.expect_text(&text)),
+				"expect-cursor" => self.expect_cursor(argument),
+				"expect-gutter" => self.expect_gutter(argument),
+				"expect-status" => parse_string(argument).and_then(|text| self.expect_status(&text)),
+				"expect-screen" => {
+					let mut rows = Vec::new();
+					while let Some((_, row)) = lines.next_if(|(_, row)| row.trim_start().starts_with('|')) {
+						rows.push(row.trim_start()[1..].trim_end().to_string());
+					}
+					self.expect_screen(&rows)
+				}
+				_ => Err(format!("Unknown command '{}'", command)),
+			};
+			result.map_err(|message| ReplayError {
+				line: line_number,
+				message,
+			})?;
+		}
+		Ok(())
+	}
+	
+	///Starts with an empty screen and status line of the typewriters grid size, as the window would after the grid changed.
+	fn clear_screen(&mut self, result: Result<(), Error>) -> Result<(), String> {
+		self.screen = Screen::new(&self.typewriter.grid);
+		self.status_line = StatusLine::default();
+		self.flush(result)
+	}
+	
+	fn type_text(&mut self, text: &str) -> Result<(), String> {
+		for letter in text.chars() {
+			if letter == '\n' {
+				self.send_key(Key::Return, KeyModifiers::NONE)?;
+				continue;
+			}
+			self.blink.reset(self.clock.now());
+			let result = self.typewriter.type_letter(&letter, &mut self.letter_instructions, self.font);
+			self.flush(result)?;
+		}
+		Ok(())
+	}
+	
+	fn press_key(&mut self, argument: &str) -> Result<(), String> {
+		let (name, repetitions) = match argument.split_once(' ') {
+			Some((name, repetitions)) => {
+				let count = repetitions.trim().strip_prefix('x')
+					.and_then(|count| count.parse::<usize>().ok())
+					.ok_or_else(|| format!("Invalid repetition '{}', expected for example 'x3'", repetitions.trim()))?;
+				(name, count)
+			}
+			None => (argument, 1),
+		};
+		let mut modifiers = KeyModifiers::NONE;
+		let mut name = name;
+		loop {
+			if let Some(rest) = name.strip_prefix("Shift+") {
+				modifiers.shift = true;
+				name = rest;
+			} else if let Some(rest) = name.strip_prefix("Ctrl+") {
+				modifiers.control = true;
+				name = rest;
+			} else if let Some(rest) = name.strip_prefix("Alt+") {
+				modifiers.alt = true;
+				name = rest;
+			} else if let Some(rest) = name.strip_prefix("AltGr+") {
+				modifiers.alt_gr = true;
+				name = rest;
+			} else {
+				break;
+			}
+		}
+		let key = match name {
+			"Backspace" => Key::Backspace,
+			"Left" => Key::Left,
+			"Right" => Key::Right,
+			"Up" => Key::Up,
+			"Down" => Key::Down,
+			"Return" => Key::Return,
+			"Space" => Key::Space,
+			"Comma" => Key::Comma,
+			"Period" => Key::Period,
+			"Slash" => Key::Slash,
+			"Minus" => Key::Minus,
+			"Backslash" => Key::Backslash,
+			"RightBracket" => Key::RightBracket,
+			"NonUsBackslash" => Key::NonUsBackslash,
+			_ => {
+				//Single letter or digit keys:
+				let mut chars = name.chars();
+				match (chars.next(), chars.next()) {
+					(Some(letter @ 'a'..='z'), None) => Key::Letter(letter),
+					(Some(digit @ '0'..='9'), None) => Key::Digit(digit as u8 - b'0'),
+					_ => return Err(format!("Unknown key '{}'", name)),
+				}
+			}
+		};
+		for _ in 0..repetitions {
+			self.send_key(key, modifiers)?;
+		}
+		Ok(())
+	}
+	
+	fn send_key(&mut self, key: Key, modifiers: KeyModifiers) -> Result<(), String> {
+		self.blink.reset(self.clock.now());
+		let result = self.typewriter.handle_input(&key, &modifiers, &mut self.letter_instructions, self.font);
+		self.flush(result)
+	}
+	
+	fn flush(&mut self, result: Result<(), Error>) -> Result<(), String> {
+		result.map_err(|error| format!("Typewriter failed: {}", error))?;
+		self.status_line.update(&self.typewriter, self.font, &mut self.letter_instructions);
+		let result = self.screen.apply(&self.letter_instructions);
+		self.letter_instructions.clear();
+		result
+	}
+	
+	fn expect_text(&self, expected: &str) -> Result<(), String> {
+		let actual = self.typewriter.memory.contents().iter()
+			.map(|id| if *id == NEWLINE { '\n' } else { *self.font.keys_by_id.get(id).unwrap_or(&char::REPLACEMENT_CHARACTER) })
+			.collect::<String>();
+		if actual != expected {
+			return Err(format!("Memory contains {:?} but expected {:?}", actual, expected));
+		}
+		Ok(())
+	}
+	
+	fn expect_cursor(&self, argument: &str) -> Result<(), String> {
+		let coordinates = argument.split_whitespace()
+			.map(|value| value.parse::<usize>())
+			.collect::<Result<Vec<usize>, _>>()
+			.map_err(|_| format!("Invalid cursor position '{}'", argument))?;
+		if coordinates.len() != 2 {
+			return Err(format!("Expected cursor position as 'x y', got '{}'", argument));
+		}
+		let actual = self.typewriter.cursor.get_draw_cursor_position();
+		if actual != (coordinates[0], coordinates[1]) {
+			return Err(format!("Cursor is at {} {} but expected {} {}", actual.0, actual.1, coordinates[0], coordinates[1]));
+		}
+		Ok(())
+	}
+	
+	fn expect_cursor_visible(&self, argument: &str) -> Result<(), String> {
+		let expected = match argument {
+			"yes" => true,
+			"no" => false,
+			_ => return Err(format!("Expected 'yes' or 'no', got '{}'", argument)),
+		};
+		if self.blink.is_visible(self.clock.now()) != expected {
+			return Err(format!("Cursor is {} but expected {}", if expected { "hidden" } else { "visible" }, if expected { "visible" } else { "hidden" }));
+		}
+		Ok(())
+	}
+	
+	fn expect_gutter(&self, argument: &str) -> Result<(), String> {
+		let actual = Gutter::line_numbers(&self.typewriter);
+		let expected = argument.split_whitespace().collect::<Vec<_>>();
+		if expected.len() > actual.len() {
+			return Err(format!("Expected gutter has {} rows, but the canvas only has {}", expected.len(), actual.len()));
+		}
+		for (y, number) in actual.iter().enumerate() {
+			let actual = number.map(|number| number.to_string()).unwrap_or_else(|| "-".to_string());
+			let expected = expected.get(y).copied().unwrap_or("-");
+			if actual != expected {
+				return Err(format!("Gutter row {} is {} but expected {}", y, actual, expected));
+			}
+		}
+		Ok(())
+	}
+	
+	fn expect_status(&self, expected: &str) -> Result<(), String> {
+		let actual = self.screen.row_text(self.typewriter.grid.status_row(), self.font).split_whitespace().collect::<Vec<_>>().join(" ");
+		if actual != expected {
+			return Err(format!("Status line is {:?} but expected {:?}", actual, expected));
+		}
+		Ok(())
+	}
+	
+	fn expect_screen(&self, rows: &[String]) -> Result<(), String> {
+		let char_height = self.typewriter.grid.char_height;
+		if rows.len() > char_height {
+			return Err(format!("Expected screen has {} rows, but the canvas only has {}", rows.len(), char_height));
+		}
+		for y in 0..char_height {
+			let expected = rows.get(y).map(|row| &row[..]).unwrap_or("");
+			let actual = self.screen.row_text(y, self.font);
+			if actual != expected {
+				return Err(format!("Screen row {} is {:?} but expected {:?}", y, actual, expected));
+			}
+		}
+		Ok(())
+	}
+}
+
+fn parse_grid(argument: &str) -> Result<GridLayout, String> {
+	let size = argument.split_whitespace()
+		.map(|value| value.parse::<usize>())
+		.collect::<Result<Vec<usize>, _>>()
+		.map_err(|_| format!("Invalid grid size '{}'", argument))?;
+	if size.len() != 2 {
+		return Err(format!("Expected grid size as 'columns rows', got '{}'", argument));
+	}
+	GridLayout::with_size(size[0], size[1])
+}
+
+///Milliseconds or 'off' (None).
+fn parse_milliseconds(argument: &str) -> Result<Option<Duration>, String> {
+	if argument == "off" {
+		return Ok(None);
+	}
+	argument.parse::<u64>()
+		.map(|milliseconds| Some(Duration::from_millis(milliseconds)))
+		.map_err(|_| format!("Invalid milliseconds '{}'", argument))
+}
+
+fn parse_string(argument: &str) -> Result<String, String> {
+	let inner = argument.strip_prefix('"')
+		.and_then(|rest| rest.strip_suffix('"'))
+		.ok_or_else(|| format!("Expected quoted string, got '{}'", argument))?;
+	let mut text = String::new();
+	let mut chars = inner.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			text.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('n') => text.push('\n'),
+			Some('\\') => text.push('\\'),
+			Some('"') => text.push('"'),
+			other => return Err(format!("Invalid escape sequence '\\{}'", other.map(String::from).unwrap_or_default())),
+		}
+	}
+	Ok(text)
+}
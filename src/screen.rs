@@ -0,0 +1,41 @@
+use crate::config::GridLayout;
+use crate::font::Font;
+use crate::typewriter::LetterInstruction;
+
+///Headless copy of the canvas (including status line), which only remembers which symbol was drawn into which slot.
+pub struct Screen {
+	pub cells: Vec<Vec<u8>>,
+}
+
+impl Default for Screen {
+	fn default() -> Self {
+		Self::new(&GridLayout::default())
+	}
+}
+
+impl Screen {
+	pub fn new(grid: &GridLayout) -> Self {
+		Self {
+			cells: vec![vec![0; grid.char_width]; grid.status_row() + 1],
+		}
+	}
+	
+	///Applies the instructions in order. Stops at the first instruction that is outside of the canvas.
+	pub fn apply(&mut self, letter_instructions: &[LetterInstruction]) -> Result<(), String> {
+		for instruction in letter_instructions {
+			if instruction.pos_y >= self.cells.len() || instruction.pos_x >= self.cells[instruction.pos_y].len() {
+				return Err(format!("Letter with ID {} drawn outside of canvas at {}/{}", instruction.id, instruction.pos_x, instruction.pos_y));
+			}
+			self.cells[instruction.pos_y][instruction.pos_x] = instruction.id;
+		}
+		Ok(())
+	}
+	
+	///Text of one row, with trailing whitespace removed. Unknown IDs are shown as replacement character.
+	pub fn row_text(&self, y: usize, font: &Font) -> String {
+		let text = self.cells[y].iter()
+			.map(|id| *font.keys_by_id.get(id).unwrap_or(&char::REPLACEMENT_CHARACTER))
+			.collect::<String>();
+		text.trim_end().to_string()
+	}
+}
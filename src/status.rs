@@ -0,0 +1,81 @@
+use std::time::Duration;
+use crate::config::MEMORY_SIZE;
+use crate::font::Font;
+use crate::layout::line_and_column;
+use crate::typewriter::{LetterInstruction, Typewriter};
+
+//How long a message is shown instead of the status, times come from a clock (see clock.rs):
+pub const MESSAGE_DURATION: Duration = Duration::from_secs(10);
+
+///Row below the text, showing cursor position, memory usage, dirty flag and mode.
+///Remembers what is currently drawn, to only redraw the letters that changed.
+#[derive(Default)]
+pub struct StatusLine {
+	drawn: Vec<u8>,
+	///Shown instead of the status until it is cleared or its time is over, which is the second value.
+	message: Option<(String, Duration)>,
+}
+
+impl StatusLine {
+	pub fn show_message(&mut self, message: String, now: Duration) {
+		self.message = Some((message, now + MESSAGE_DURATION));
+	}
+	
+	pub fn clear_message(&mut self) {
+		self.message = None;
+	}
+	
+	///Time until the message disappears, None without message.
+	pub fn until_expiry(&self, now: Duration) -> Option<Duration> {
+		self.message.as_ref().map(|(_, until)| until.saturating_sub(now))
+	}
+	
+	///Removes the message once its time is over.
+	pub fn expire(&mut self, now: Duration) {
+		if self.until_expiry(now).is_some_and(|remaining| remaining.is_zero()) {
+			self.message = None;
+		}
+	}
+	
+	///The canvas got cleared, draw the whole row again on the next update (keeping the message).
+	pub fn reset(&mut self) {
+		self.drawn.clear();
+	}
+	
+	pub fn update(&mut self, typewriter: &Typewriter, font: &Font, letter_instructions: &mut Vec<LetterInstruction>) {
+		let grid = &typewriter.grid;
+		//Starts out empty, like the canvas:
+		if self.drawn.len() != grid.char_width {
+			self.drawn = vec![0; grid.char_width];
+		}
+		let text = self.message.as_ref().map_or_else(|| Self::text(typewriter), |(message, _)| message.clone());
+		let mut letters = text.chars();
+		for x in 0..grid.char_width {
+			//Letters which the font does not have are left empty:
+			let id = letters.next().and_then(|letter| font.ids_by_key.get(&letter)).copied().unwrap_or(0);
+			if self.drawn[x] != id {
+				self.drawn[x] = id;
+				letter_instructions.push(LetterInstruction {
+					pos_x: x,
+					pos_y: grid.status_row(),
+					id,
+				});
+			}
+		}
+	}
+	
+	pub fn text(typewriter: &Typewriter) -> String {
+		let memory = &typewriter.memory;
+		let (line, column) = line_and_column(&memory.memory[..memory.pointer_before_cursor]);
+		let left = format!("L{} C{}", line + 1, column + 1);
+		let right = format!(
+			"{}{}/{} {}",
+			if typewriter.dirty { "* " } else { "" },
+			typewriter.memory.used(),
+			MEMORY_SIZE,
+			typewriter.wrap_mode.name(),
+		);
+		let padding = typewriter.grid.char_width.saturating_sub(left.chars().count() + right.chars().count()).max(1);
+		format!("{}{}{}", left, " ".repeat(padding), right)
+	}
+}
@@ -0,0 +1,195 @@
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::path::Path;
+
+//Themes in addition to the ones in the code, built into the binary like the font:
+pub const DEFAULT_THEMES: &str = include_str!("../Themes.txt");
+pub const DEFAULT_THEMES_NAME: &str = "Themes.txt (built-in)";
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Color {
+	pub r: u8,
+	pub g: u8,
+	pub b: u8,
+}
+
+impl Color {
+	pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+		Self { r, g, b }
+	}
+	
+	///Parses '#rrggbb'.
+	fn parse(value: &str) -> Option<Self> {
+		let hex = value.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii())?;
+		let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+		Some(Self::rgb(channel(0)?, channel(2)?, channel(4)?))
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Theme {
+	pub name: String,
+	pub text: Color,
+	pub background: Color,
+	pub cursor: Color,
+	///Not drawn until there is a text selection.
+	pub selection: Color,
+	///Status line and its separator.
+	pub status: Color,
+	///Line numbers.
+	pub gutter: Color,
+}
+
+impl Theme {
+	///Theme where every element is drawn in the text color.
+	fn plain(name: &str, text: Color, background: Color) -> Self {
+		Self {
+			name: name.to_string(),
+			text,
+			background,
+			cursor: text,
+			selection: text,
+			status: text,
+			gutter: text,
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ThemeError {
+	Read(String),
+	InvalidLine(usize, String),
+	InvalidColor(usize, String),
+	UnknownElement(usize, String),
+	MissingColor(String),
+	///Any of the other errors, with the theme file it was found in.
+	InFile {
+		file: String,
+		error: Box<ThemeError>,
+	},
+}
+
+impl ThemeError {
+	///Few words about the error, short enough for the status line.
+	pub fn summary(&self) -> String {
+		match self {
+			ThemeError::InFile { error, .. } => error.summary(),
+			ThemeError::Read(_) => "Themes: cannot read file".to_string(),
+			ThemeError::InvalidLine(line, _) => format!("Themes L{}: invalid line", line),
+			ThemeError::InvalidColor(line, _) => format!("Themes L{}: invalid color", line),
+			ThemeError::UnknownElement(line, _) => format!("Themes L{}: unknown element", line),
+			ThemeError::MissingColor(_) => "Themes: text or background missing".to_string(),
+		}
+	}
+}
+
+impl Display for ThemeError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ThemeError::Read(error) => write!(f, "Failed to read file: {}", error),
+			ThemeError::InvalidLine(line, text) => write!(f, "Line {}: Theme file has invalid line: '{}'", line, text),
+			ThemeError::InvalidColor(line, value) => write!(f, "Line {}: Expected color as '#rrggbb', got '{}'", line, value),
+			ThemeError::UnknownElement(line, name) => write!(f, "Line {}: Unknown theme element '{}'", line, name),
+			ThemeError::MissingColor(theme) => write!(f, "Theme '{}' needs at least the text and background color.", theme),
+			ThemeError::InFile { file, error } => write!(f, "{}: {}", file, error),
+		}
+	}
+}
+
+pub fn built_in_themes() -> Vec<Theme> {
+	vec![
+		Theme {
+			gutter: Color::rgb(30, 130, 30),
+			..Theme::plain("green", Color::rgb(50, 255, 50), Color::rgb(10, 15, 10))
+		},
+		Theme {
+			gutter: Color::rgb(140, 90, 0),
+			..Theme::plain("amber", Color::rgb(255, 176, 0), Color::rgb(20, 12, 0))
+		},
+		Theme {
+			gutter: Color::rgb(120, 120, 120),
+			..Theme::plain("white", Color::rgb(230, 230, 230), Color::rgb(12, 12, 12))
+		},
+		//Like the lamps of a display in LW, bright when on and gray when off:
+		Theme {
+			status: Color::rgb(220, 220, 220),
+			gutter: Color::rgb(150, 150, 150),
+			..Theme::plain("lamp", Color::rgb(255, 40, 40), Color::rgb(55, 55, 55))
+		},
+	]
+}
+
+///Themes from the code, followed by the ones built into the binary with the theme file.
+pub fn load_themes() -> Result<Vec<Theme>, ThemeError> {
+	let mut themes = built_in_themes();
+	add_themes(&mut themes, DEFAULT_THEMES, DEFAULT_THEMES_NAME)?;
+	Ok(themes)
+}
+
+///Default themes, followed by the ones from a theme file. A theme with the same name as a default one replaces it.
+pub fn load_theme_file(path: &Path) -> Result<Vec<Theme>, ThemeError> {
+	let file = path.display().to_string();
+	let text = read_to_string(path).map_err(|error| ThemeError::InFile { file: file.clone(), error: Box::new(ThemeError::Read(error.to_string())) })?;
+	let mut themes = load_themes()?;
+	add_themes(&mut themes, &text, &file)?;
+	Ok(themes)
+}
+
+fn add_themes(themes: &mut Vec<Theme>, text: &str, file: &str) -> Result<(), ThemeError> {
+	let parsed = parse_themes(text).map_err(|error| ThemeError::InFile { file: file.to_string(), error: Box::new(error) })?;
+	for theme in parsed {
+		match themes.iter_mut().find(|existing| existing.name == theme.name) {
+			Some(existing) => *existing = theme,
+			None => themes.push(theme),
+		}
+	}
+	Ok(())
+}
+
+///Parses themes in this format, elements which are not given use the text color:
+/// [name]
+/// text = #32ff32
+/// background = #0a0f0a
+/// cursor / selection / status / gutter = #rrggbb
+///Empty lines and lines starting with '#' are ignored.
+pub fn parse_themes(text: &str) -> Result<Vec<Theme>, ThemeError> {
+	//Collect the given colors per theme first, as the defaults depend on the text color:
+	let mut sections: Vec<(String, Vec<(String, Color)>)> = Vec::new();
+	for (index, line) in text.lines().enumerate() {
+		let line_number = index + 1;
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+			sections.push((name.trim().to_string(), Vec::new()));
+			continue;
+		}
+		let (element, value) = line.split_once('=').ok_or_else(|| ThemeError::InvalidLine(line_number, line.to_string()))?;
+		let (element, value) = (element.trim(), value.trim());
+		if !["text", "background", "cursor", "selection", "status", "gutter"].contains(&element) {
+			return Err(ThemeError::UnknownElement(line_number, element.to_string()));
+		}
+		let color = Color::parse(value).ok_or_else(|| ThemeError::InvalidColor(line_number, value.to_string()))?;
+		let (_, colors) = sections.last_mut().ok_or_else(|| ThemeError::InvalidLine(line_number, line.to_string()))?;
+		colors.push((element.to_string(), color));
+	}
+	
+	let mut themes = Vec::new();
+	for (name, colors) in sections {
+		let color = |element: &str| colors.iter().rev().find(|(name, _)| name == element).map(|(_, color)| *color);
+		let (Some(text), Some(background)) = (color("text"), color("background")) else {
+			return Err(ThemeError::MissingColor(name));
+		};
+		themes.push(Theme {
+			name,
+			text,
+			background,
+			cursor: color("cursor").unwrap_or(text),
+			selection: color("selection").unwrap_or(text),
+			status: color("status").unwrap_or(text),
+			gutter: color("gutter").unwrap_or(text),
+		});
+	}
+	Ok(themes)
+}
@@ -1,7 +1,10 @@
-use sdl2::keyboard::{Mod, Scancode};
-use crate::config::{CHAR_HEIGHT, CHAR_WIDTH, NEWLINE};
+use std::ops::Range;
+use crate::config::{GridLayout, NEWLINE};
 use crate::cursor::CursorPointer;
+use crate::error::Error;
 use crate::font::Font;
+use crate::input::{Key, KeyModifiers};
+use crate::layout::{cursor_row, line_and_column, line_end_column, line_rows, visual_rows, VisualRow, WrapMode};
 use crate::memory::Memory;
 
 pub struct LetterInstruction {
@@ -11,85 +14,85 @@ pub struct LetterInstruction {
 }
 
 pub struct Typewriter {
+	///Size of the text grid, the cursor uses the same one.
+	pub grid: GridLayout,
 	//Cursor pointer:
 	pub cursor: CursorPointer,
 	pub memory: Memory,
-	pub line_cache: [usize; CHAR_HEIGHT],
+	pub line_cache: Vec<usize>,
+	///Set whenever the text got modified, reset it after saving.
+	pub dirty: bool,
+	pub wrap_mode: WrapMode,
+	//First row and column visible on the canvas, only used when drawing from the layout:
+	view_row: usize,
+	view_column: usize,
+	//Rows of the document since the last time it was drawn from the layout:
+	layout: Vec<VisualRow>,
 }
 
 impl Default for Typewriter {
 	fn default() -> Self {
-		Self {
-			cursor: CursorPointer::default(),
-			memory: Memory::default(),
-			line_cache: [0; CHAR_HEIGHT],
-		}
+		Self::new(GridLayout::default())
 	}
 }
 
 macro_rules! assert_false {
 	($falsey_condition:expr, $failure_message:expr) => {
 		if $falsey_condition {
-			println!($failure_message);
-			return;
+			return Err(Error::Corruption($failure_message));
 		}
 	};
 }
 
 impl Typewriter {
+	pub fn new(grid: GridLayout) -> Self {
+		Self {
+			grid,
+			cursor: CursorPointer::new(grid),
+			memory: Memory::default(),
+			line_cache: vec![0; grid.char_height],
+			dirty: false,
+			wrap_mode: WrapMode::Wrap,
+			view_row: 0,
+			view_column: 0,
+			layout: Vec::new(),
+		}
+	}
+	
 	pub fn handle_input(
 		&mut self,
-		scancode: &Scancode,
-		keymod: &Mod,
+		key: &Key,
+		modifiers: &KeyModifiers,
 		letter_instructions: &mut Vec<LetterInstruction>,
 		font: &Font,
-	) {
+	) -> Result<(), Error> {
 		// println!("{} - {}", self.memory.pointer_before_cursor, self.memory.pointer_after_cursor);
-		let typed_letter = self.resolve_symbol(scancode, keymod);
-		if typed_letter.is_some() {
-			if self.memory.memory_full() {
-				//TODO: Bell.
-				return;
-			}
-			
-			let letter = &typed_letter.unwrap();
-			self.memory.insert(*font.ids_by_key.get(letter).unwrap());
-			//Only write a letter, when it visibly fits onto the canvas, else the move cursor redrawing logic will draw it.
-			if !self.cursor.is_at_canvas_end() {
-				self.write_space(letter_instructions);
-				self.write_letter_by_key(font, letter_instructions, letter);
+		//Control and Alt are for shortcuts, unless they are AltGr which types symbols:
+		if (modifiers.control || modifiers.alt) && !modifiers.is_alt_gr() {
+			//Shortcuts, there are none with Alt yet:
+			if modifiers.control && *key == Key::Letter('w') {
+				return self.set_wrap_mode(self.wrap_mode.next(), letter_instructions, font);
 			}
-			
-			if self.cursor.is_at_canvas_end() {
-				//First move the cursor to the start of the line:
-				self.cursor.to_line_beginning();
-				//Then clear basically the whole screen:
-				self.clear_above_cursor(letter_instructions);
-				self.clear_from_cursor(letter_instructions);
-				//Update the line cache (to know where to put the cursor) and redraw the screen:
-				self.update_line_cache();
-				self.redraw_before_cursor(letter_instructions);
-			} else {
-				self.cursor.increment();
-			}
-			
-			//As we added a letter, there is always the demand to redraw things after the cursor,
-			// unless the cursor is at the end of the memory or at the end of canvas (stuff is out of bounds then):
-			if !self.memory.cursor_at_end() && !self.cursor.is_at_canvas_end() {
-				self.clear_from_cursor(letter_instructions);
-				self.clear_below_cursor(letter_instructions);
-				self.redraw_from_cursor(letter_instructions);
-			}
-			return;
+			return Ok(());
+		}
+		
+		let typed_letter = self.resolve_symbol(key, modifiers);
+		if let Some(letter) = &typed_letter {
+			return self.type_letter(letter, letter_instructions, font);
+		}
+		
+		if self.wrap_mode != WrapMode::Wrap {
+			return self.handle_key_by_layout(key, letter_instructions, font);
 		}
 		
-		match scancode {
-			Scancode::Backspace => {
+		match key {
+			Key::Backspace => {
 				if self.memory.cursor_at_beginning() {
 					//TODO: Bell.
-					return;
+					return Ok(());
 				}
 				self.memory.delete_backwards();
+				self.dirty = true;
 				//When we remove a character, it has to be replaced with an empty space (whitespace).
 				//That has to be manually drawn here, but:
 				// - If the cursor is in column 0, a (invisible) newline will be deleted.
@@ -97,24 +100,24 @@ impl Typewriter {
 				//The cursor movement logic, should not be bothered/polluted with non-redrawing display changes.
 				if self.memory.cursor_at_end() && self.cursor.x != 0 {
 					self.cursor.x -= 1;
-					self.write_space(letter_instructions);
+					self.write_space(letter_instructions)?;
 					self.cursor.x += 1;
 				}
 				//Now after the action performed, move the cursor back properly:
-				self.move_cursor_back(letter_instructions, true);
+				self.move_cursor_back(letter_instructions, true)?;
 			}
-			Scancode::Left => {
+			Key::Left => {
 				if self.memory.cursor_at_beginning() {
 					//TODO: Bell.
-					return;
+					return Ok(());
 				}
 				self.memory.move_after_cursor();
-				self.move_cursor_back(letter_instructions, false);
+				self.move_cursor_back(letter_instructions, false)?;
 			}
-			Scancode::Right => {
+			Key::Right => {
 				if self.memory.cursor_at_end() {
 					//TODO: Bell.
-					return;
+					return Ok(());
 				}
 				self.memory.move_before_cursor();
 				
@@ -149,22 +152,23 @@ impl Typewriter {
 					self.clear_from_cursor(letter_instructions);
 					//Update the line cache (to know where to put the cursor) and redraw the screen:
 					self.update_line_cache();
-					self.redraw_before_cursor(letter_instructions);
+					self.redraw_before_cursor(letter_instructions)?;
 					
 					//In case that there was text after the cursor redraw that as well:
 					if !self.memory.cursor_at_end() {
-						self.redraw_from_cursor(letter_instructions);
+						self.redraw_from_cursor(letter_instructions)?;
 					}
 				}
 			}
-			Scancode::Return => {
+			Key::Return => {
 				if self.memory.memory_full() {
 					//TODO: Bell
-					return; //Memory full, cannot insert
+					return Ok(()); //Memory full, cannot insert
 				}
 				
 				//Inject the newline into memory:
 				self.memory.insert(NEWLINE);
+				self.dirty = true;
 				
 				if self.cursor.is_last_line() {
 					//At the end of display, stuff has to be shifted!
@@ -175,7 +179,7 @@ impl Typewriter {
 					self.clear_above_cursor(letter_instructions);
 					//Draw everything before the cursor:
 					self.update_line_cache();
-					self.redraw_before_cursor(letter_instructions);
+					self.redraw_before_cursor(letter_instructions)?;
 					
 					//Clear everything after the cursor (chances are high, this is mandatory - except when cursor was in first column):
 					self.clear_from_cursor(letter_instructions);
@@ -193,15 +197,133 @@ impl Typewriter {
 				}
 				
 				if !self.memory.cursor_at_end() {
-					self.redraw_from_cursor(letter_instructions);
+					self.redraw_from_cursor(letter_instructions)?;
 				}
 			}
 			_ => {}
 		}
+		Ok(())
+	}
+	
+	///Inserts a letter at the cursor position, as if its key was pressed.
+	pub fn type_letter(&mut self, letter: &char, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		if self.wrap_mode != WrapMode::Wrap {
+			return self.type_letter_by_layout(letter, letter_instructions, font);
+		}
+		if self.memory.memory_full() {
+			//TODO: Bell.
+			return Ok(());
+		}
+		
+		//Letters the font does not have are kept, to be drawn as replacement symbol:
+		self.memory.insert(font.id(*letter));
+		self.dirty = true;
+		//Only write a letter, when it visibly fits onto the canvas, else the move cursor redrawing logic will draw it.
+		if !self.cursor.is_at_canvas_end() {
+			self.write_space(letter_instructions)?;
+			self.write_letter_by_key(font, letter_instructions, letter)?;
+		}
+		
+		if self.cursor.is_at_canvas_end() {
+			//First move the cursor to the start of the line:
+			self.cursor.to_line_beginning();
+			//Then clear basically the whole screen:
+			self.clear_above_cursor(letter_instructions);
+			self.clear_from_cursor(letter_instructions);
+			//Update the line cache (to know where to put the cursor) and redraw the screen:
+			self.update_line_cache();
+			self.redraw_before_cursor(letter_instructions)?;
+		} else {
+			self.cursor.increment();
+		}
+		
+		//As we added a letter, there is always the demand to redraw things after the cursor,
+		// unless the cursor is at the end of the memory or at the end of canvas (stuff is out of bounds then):
+		if !self.memory.cursor_at_end() && !self.cursor.is_at_canvas_end() {
+			self.clear_from_cursor(letter_instructions);
+			self.clear_below_cursor(letter_instructions);
+			self.redraw_from_cursor(letter_instructions)?;
+		}
+		Ok(())
+	}
+	
+	///Switches the wrap mode, keeping the cursor in its canvas row if possible, and redraws everything.
+	pub fn set_wrap_mode(&mut self, wrap_mode: WrapMode, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		if self.wrap_mode == wrap_mode {
+			return Ok(());
+		}
+		self.wrap_mode = wrap_mode;
+		self.relayout(letter_instructions, font)
+	}
+	
+	///Switches to another grid size (for example after the window got resized), keeping the cursor in its canvas row if possible, and redraws everything.
+	pub fn set_grid(&mut self, grid: GridLayout, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		let y = self.cursor.y.min(grid.char_height_upper_bound());
+		self.grid = grid;
+		self.cursor = CursorPointer::new(grid);
+		self.cursor.y = y;
+		self.line_cache = vec![0; grid.char_height];
+		self.relayout(letter_instructions, font)
+	}
+	
+	///Places the cursor according to the current wrap mode and grid, then redraws everything.
+	fn relayout(&mut self, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		let contents = self.memory.contents();
+		let rows = visual_rows(&contents, self.wrap_mode, self.grid.char_width);
+		let row = cursor_row(&rows, self.memory.pointer_before_cursor, self.grid.char_width);
+		self.cursor.y = self.cursor.y.min(row);
+		match self.wrap_mode {
+			WrapMode::Wrap => {
+				self.cursor.x = self.memory.pointer_before_cursor - rows[row].start;
+				self.repaint(letter_instructions, font)
+			}
+			WrapMode::NoWrap | WrapMode::WordWrap => {
+				self.view_row = row - self.cursor.y;
+				self.view_column = 0;
+				self.layout = rows;
+				self.update_layout_view();
+				self.redraw_layout_lines(&contents, 0..usize::MAX, letter_instructions, font)
+			}
+		}
+	}
+	
+	///Redraws the whole canvas from memory, without moving the cursor.
+	///Meant to recover the canvas after an error was reported.
+	pub fn repaint(&mut self, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		if self.wrap_mode != WrapMode::Wrap {
+			let contents = self.memory.contents();
+			self.layout = visual_rows(&contents, self.wrap_mode, self.grid.char_width);
+			return self.redraw_layout_lines(&contents, 0..usize::MAX, letter_instructions, font);
+		}
+		for y in 0..self.grid.char_height {
+			for x in 0..self.grid.char_width {
+				letter_instructions.push(LetterInstruction {
+					pos_x: x,
+					pos_y: y,
+					id: 0,
+				});
+			}
+		}
+		if !self.memory.cursor_at_beginning() && !self.cursor.is_at_canvas_start() {
+			self.update_line_cache();
+			self.redraw_before_cursor(letter_instructions)?;
+		}
+		if !self.memory.cursor_at_end() {
+			self.redraw_from_cursor(letter_instructions)?;
+		}
+		Ok(())
+	}
+	
+	///The symbol right after the cursor, which a block cursor covers. None at the end of a line or of the document.
+	pub fn symbol_after_cursor(&self) -> Option<u8> {
+		if self.memory.cursor_at_end() {
+			return None;
+		}
+		Some(self.memory.memory[self.memory.pointer_after_cursor + 1]).filter(|value| *value != NEWLINE)
 	}
 	
 	///Moves cursor back, handling edge cases & redrawing. AFTER the memory-cursor has already been moved.
-	fn move_cursor_back(&mut self, letter_instructions: &mut Vec<LetterInstruction>, mut must_update_after_cursor: bool) {
+	fn move_cursor_back(&mut self, letter_instructions: &mut Vec<LetterInstruction>, mut must_update_after_cursor: bool) -> Result<(), Error> {
 		//We either pressed Backspace or Arrow-Left.
 		//The cursor moved from second or first column one slot backwards. Both cases have edge cases.
 		
@@ -209,10 +331,14 @@ impl Typewriter {
 			let mut go_line_up = false;
 			if self.cursor.x == 0 {
 				//We are either at the beginning of the document, or we can expect to delete a newline.
-				assert_false!(self.memory.memory[self.memory.pointer_before_cursor] != NEWLINE, "VIOLATION/CORRUPTION: Attempted to move cursor back, while in first column, but the character was not a newline!");
+				assert_false!(self.memory.memory[self.memory.pointer_before_cursor] != NEWLINE, "Attempted to move cursor back, while in first column, but the character was not a newline!");
 				
 				//When at the beginning and in first column, the cursor was moved from the second line/row to the first (empty) line/row:
 				if self.memory.cursor_at_beginning() {
+					if self.cursor.is_first_line() {
+						//The first line was scrolled out of view (like on a grid with only one row), the rows have to move down:
+						must_update_after_cursor = true;
+					}
 					self.cursor.y = 0;
 				} else {
 					//Else we have to set the cursor horizontally to match the previous line:
@@ -223,7 +349,7 @@ impl Typewriter {
 				//We can either remove the first letter of a line, or remove a letter wrapping to the previous line.
 				
 				//If we removed a NEWLINE, data is corrupted or the cursor was at the wrong position.
-				assert_false!(self.memory.memory[self.memory.pointer_before_cursor] == NEWLINE, "VIOLATION/CORRUPTION: Attempted to move cursor back, while in second column, but the character was a newline!");
+				assert_false!(self.memory.memory[self.memory.pointer_before_cursor] == NEWLINE, "Attempted to move cursor back, while in second column, but the character was a newline!");
 				
 				//We removed a letter. Next check if we are in a wrapped or empty line:
 				if self.memory.cursor_at_beginning() || self.memory.memory[self.memory.pointer_before_cursor - 1] == NEWLINE {
@@ -231,7 +357,7 @@ impl Typewriter {
 					self.cursor.x = 0;
 				} else {
 					//A wrapped line, in this case we have to move the cursor to the very last position in the previous line.
-					self.cursor.x = CHAR_WIDTH;
+					self.cursor.x = self.grid.char_width;
 					go_line_up = true;
 				}
 			}
@@ -241,7 +367,7 @@ impl Typewriter {
 				if self.cursor.is_first_line() {
 					//In the first line, we need to redraw the canvas:
 					self.clear_to_cursor(letter_instructions);
-					self.redraw_before_cursor_top_line(letter_instructions);
+					self.redraw_before_cursor_top_line(letter_instructions)?;
 					//Also redraw anything after the cursor:
 					must_update_after_cursor = true;
 				} else {
@@ -250,8 +376,8 @@ impl Typewriter {
 				}
 			}
 		} else {
-			assert_false!(self.memory.cursor_at_beginning(), "VIOLATION: Attempted to move cursor one column back, while not in first column, but memory is empty!");
-			assert_false!(self.memory.memory[self.memory.pointer_before_cursor] == NEWLINE, "VIOLATION/CORRUPTION: Attempted to move cursor back, while not in first column, but the character was a newline!");
+			assert_false!(self.memory.cursor_at_beginning(), "Attempted to move cursor one column back, while not in first column, but memory is empty!");
+			assert_false!(self.memory.memory[self.memory.pointer_before_cursor] == NEWLINE, "Attempted to move cursor back, while not in first column, but the character was a newline!");
 			
 			//We are somewhere within a line and can simply decrement the cursor without fear.
 			self.cursor.decrement();
@@ -261,12 +387,13 @@ impl Typewriter {
 		if must_update_after_cursor && !self.memory.cursor_at_end() {
 			self.clear_from_cursor(letter_instructions);
 			self.clear_below_cursor(letter_instructions);
-			self.redraw_from_cursor(letter_instructions);
+			self.redraw_from_cursor(letter_instructions)?;
 		}
+		Ok(())
 	}
 	
 	fn clear_from_cursor(&self, letter_instructions: &mut Vec<LetterInstruction>) {
-		for x in self.cursor.x..CHAR_WIDTH {
+		for x in self.cursor.x..self.grid.char_width {
 			letter_instructions.push(LetterInstruction {
 				pos_x: x,
 				pos_y: self.cursor.y,
@@ -276,7 +403,8 @@ impl Typewriter {
 	}
 	
 	fn clear_to_cursor(&self, letter_instructions: &mut Vec<LetterInstruction>) {
-		for x in 0..=self.cursor.x {
+		//The cursor can be behind the last column, which has no slot to clear:
+		for x in 0..=self.cursor.x.min(self.grid.char_width_upper_bound()) {
 			letter_instructions.push(LetterInstruction {
 				pos_x: x,
 				pos_y: self.cursor.y,
@@ -286,8 +414,8 @@ impl Typewriter {
 	}
 	
 	fn clear_below_cursor(&self, letter_instructions: &mut Vec<LetterInstruction>) {
-		for y in (self.cursor.y + 1)..CHAR_HEIGHT {
-			for x in 0..CHAR_WIDTH {
+		for y in (self.cursor.y + 1)..self.grid.char_height {
+			for x in 0..self.grid.char_width {
 				letter_instructions.push(LetterInstruction {
 					pos_x: x,
 					pos_y: y,
@@ -299,7 +427,7 @@ impl Typewriter {
 	
 	fn clear_above_cursor(&self, letter_instructions: &mut Vec<LetterInstruction>) {
 		for y in 0..self.cursor.y {
-			for x in 0..CHAR_WIDTH {
+			for x in 0..self.grid.char_width {
 				letter_instructions.push(LetterInstruction {
 					pos_x: x,
 					pos_y: y,
@@ -310,94 +438,59 @@ impl Typewriter {
 	}
 	
 	fn update_line_cache(&mut self) {
-		let mut mem_index = self.memory.pointer_before_cursor - 1;
-		if mem_index == 0 {
-			//This should never happen, handle it anyway:
-			self.line_cache[0] = 0;
-			return;
-		}
-		let mut line_index = 0;
-		let mut counter = 0;
+		let before_cursor = &self.memory.memory[..self.memory.pointer_before_cursor];
+		let mut line_end = before_cursor.len();
 		let mut visible_lines = 0;
 		
-		// println!("Building line cache:");
-		loop {
-			let value = self.memory.memory[mem_index];
-			// println!(":: {}", value);
-			if value != NEWLINE {
-				counter += 1;
-				if counter > CHAR_WIDTH {
-					// println!("- WRAP.");
-					counter = 1;
-					visible_lines += 1;
-				}
-			}
-			
-			if value == NEWLINE || mem_index == 0 {
-				self.line_cache[line_index] = counter;
-				// println!("- {}: {}", line_index, counter);
-				counter = 0;
-				line_index += 1;
-				visible_lines += 1;
-				if visible_lines >= CHAR_HEIGHT {
-					break;
-				}
-			}
-			
-			if mem_index == 0 {
+		for line_index in 0..self.grid.char_height {
+			let line_start = before_cursor[..line_end].iter().rposition(|value| *value == NEWLINE).map_or(0, |index| index + 1);
+			let line = &before_cursor[line_start..line_end];
+			self.line_cache[line_index] = line_end_column(line, self.wrap_mode, self.grid.char_width);
+			visible_lines += line_rows(line, self.wrap_mode, self.grid.char_width).len();
+			if visible_lines >= self.grid.char_height || line_start == 0 {
 				break;
 			}
-			mem_index -= 1;
+			line_end = line_start - 1; //Skip the newline.
 		}
 	}
 	
 	///Returns the cursor position for the line before the cursor.
 	fn find_line_end(&self) -> usize {
-		let mem = &self.memory;
-		let mut counter = 0;
-		let mut index = mem.pointer_before_cursor;
-		//TODO: How does this handle a single letter on screen? RIP.
-		while index != 0 {
-			index -= 1;
-			
-			let value = mem.memory[index];
-			if value == NEWLINE {
-				return counter;
-			}
-			
-			counter += 1;
-			if counter > CHAR_WIDTH {
-				counter = 1;
-			}
-		}
-		counter
+		let before_cursor = &self.memory.memory[..self.memory.pointer_before_cursor];
+		let line_start = before_cursor.iter().rposition(|value| *value == NEWLINE).map_or(0, |index| index + 1);
+		line_end_column(&before_cursor[line_start..], self.wrap_mode, self.grid.char_width)
 	}
 	
 	//Will redraw only the top line, ignoring all constrains. Assumes a valid state where there is enough to draw without newlines, ignoring correct line offset.
 	//Call-Requirement:
 	// - Cursor X position must be correct.
-	fn redraw_before_cursor_top_line(&mut self, letter_instructions: &mut Vec<LetterInstruction>) {
+	fn redraw_before_cursor_top_line(&mut self, letter_instructions: &mut Vec<LetterInstruction>) -> Result<(), Error> {
 		// println!("Redrawing top line!");
 		self.cursor.backup();
 		
 		let mut pointer = self.memory.pointer_before_cursor - 1; //Get the stack (value) position
 		
+		let mut result = Ok(());
 		loop {
 			if self.cursor.is_at_line_start() {
 				break;
 			}
 			self.cursor.decrement_slot();
-			self.write_letter_by_id(letter_instructions, self.memory.memory[pointer]);
+			result = self.write_letter_by_id(letter_instructions, self.memory.memory[pointer]);
+			if result.is_err() || pointer == 0 {
+				break; //Failed or done processing memory.
+			}
 			pointer -= 1;
 		}
 		
 		self.cursor.restore();
+		result
 	}
 	
 	//Call-Requirement:
 	// - Pointer must not be at beginning of memory!
 	// - Cursor must not be at canvas start!
-	fn redraw_before_cursor(&mut self, letter_instructions: &mut Vec<LetterInstruction>) {
+	fn redraw_before_cursor(&mut self, letter_instructions: &mut Vec<LetterInstruction>) -> Result<(), Error> {
 		// println!("Redrawing before cursor!");
 		self.cursor.backup();
 		
@@ -405,10 +498,11 @@ impl Typewriter {
 		
 		let new_cursor_position = self.line_cache[line_index];
 		//The new cursor position needs to be subtracted by 1, to have the first letter drawing position. To prevent overflow, handle 0 first.
-		self.cursor.x = (if new_cursor_position == 0 { CHAR_WIDTH } else { new_cursor_position }) - 1;
+		self.cursor.x = (if new_cursor_position == 0 { self.grid.char_width } else { new_cursor_position }) - 1;
 		
 		let mut pointer = self.memory.pointer_before_cursor - 1; //Get the stack (value) position
 		
+		let mut result = Ok(());
 		let mut just_had_line_wrap = false;
 		loop {
 			let value = self.memory.memory[pointer];
@@ -416,7 +510,7 @@ impl Typewriter {
 				//Encountered newline.
 				// println!("> NL");
 				line_index += 1;
-				if line_index == CHAR_HEIGHT {
+				if line_index == self.grid.char_height {
 					// println!(">> Last line: {}", line_index);
 					break; //Drawn last line already.
 				}
@@ -429,11 +523,15 @@ impl Typewriter {
 					self.cursor.to_previous_line();
 				}
 				just_had_line_wrap = false;
-				self.cursor.x = self.line_cache[line_index] - 1;
+				//Empty lines draw no letter, thus their position does not matter, but must not underflow:
+				self.cursor.x = self.line_cache[line_index].saturating_sub(1);
 			} else {
 				// println!("> L: {}", value);
 				// self.write_space(letter_instructions);
-				self.write_letter_by_id(letter_instructions, value);
+				result = self.write_letter_by_id(letter_instructions, value);
+				if result.is_err() {
+					break;
+				}
 				//Move cursor:
 				if self.cursor.is_at_canvas_start() {
 					// println!(">> Cursor at start.");
@@ -452,17 +550,22 @@ impl Typewriter {
 		
 		self.cursor.restore();
 		self.cursor.x = new_cursor_position;
+		result
 	}
 	
 	//Call-Requirement:
 	// - Memory-Cursor must not be at the end of memory.
-	fn redraw_from_cursor(&mut self, letter_instructions: &mut Vec<LetterInstruction>) {
+	fn redraw_from_cursor(&mut self, letter_instructions: &mut Vec<LetterInstruction>) -> Result<(), Error> {
+		if self.cursor.is_at_canvas_end() {
+			return Ok(()); //No slot left to draw into (happens with a single row).
+		}
 		self.cursor.backup();
 		
 		//By calling requirement, it is guaranteed that the after-caret stack is not empty.
 		let mut pointer = self.memory.pointer_after_cursor;
 		pointer += 1; //Jump to the first value instead of the free slot.
 		
+		let mut result = Ok(());
 		loop {
 			//Get value from after-caret stack:
 			let value = self.memory.memory[pointer];
@@ -474,7 +577,10 @@ impl Typewriter {
 				self.cursor.to_line_beginning();
 			} else {
 				//Draw that symbol, but first clear the area:
-				self.write_letter_by_id(letter_instructions, value);
+				result = self.write_letter_by_id(letter_instructions, value);
+				if result.is_err() {
+					break;
+				}
 				
 				if self.cursor.is_last_line() && self.cursor.is_at_line_end() {
 					break; //Reached the end of display, no space to draw more.
@@ -491,89 +597,250 @@ impl Typewriter {
 		}
 		
 		self.cursor.restore();
+		result
 	}
 	
-	fn write_space(&self, letter_instructions: &mut Vec<LetterInstruction>) {
-		self.write_letter_by_id(letter_instructions, 0);
+	fn write_space(&self, letter_instructions: &mut Vec<LetterInstruction>) -> Result<(), Error> {
+		self.write_letter_by_id(letter_instructions, 0)
 	}
 	
-	fn write_letter_by_key(&self, font: &Font, letter_instructions: &mut Vec<LetterInstruction>, letter_key: &char) {
-		self.write_letter_by_id(letter_instructions, *font.ids_by_key.get(letter_key).unwrap());
+	fn write_letter_by_key(&self, font: &Font, letter_instructions: &mut Vec<LetterInstruction>, letter_key: &char) -> Result<(), Error> {
+		self.write_letter_by_id(letter_instructions, font.id(*letter_key))
 	}
 	
-	fn write_letter_by_id(&self, letter_instructions: &mut Vec<LetterInstruction>, letter_id: u8) {
-		let (x, y) = self.cursor.get_draw_letter_position();
+	fn write_letter_by_id(&self, letter_instructions: &mut Vec<LetterInstruction>, letter_id: u8) -> Result<(), Error> {
+		let (x, y) = self.cursor.get_draw_letter_position()?;
 		letter_instructions.push(LetterInstruction {
 			pos_x: x,
 			pos_y: y,
 			id: letter_id,
 		});
+		Ok(())
 	}
 	
-	fn resolve_symbol(&mut self, keycode: &Scancode, keymod: &Mod) -> Option<char> {
-		let raw_keycode = *keycode as u32;
-		let is_shift = (*keymod & Mod::LSHIFTMOD) == Mod::LSHIFTMOD || (*keymod & Mod::RSHIFTMOD) == Mod::RSHIFTMOD;
+	fn resolve_symbol(&mut self, key: &Key, modifiers: &KeyModifiers) -> Option<char> {
+		//AltGr symbols of the German layout:
+		if modifiers.is_alt_gr() {
+			return match key {
+				Key::Letter('q') => Some('@'),
+				Key::Digit(7) => Some('{'),
+				Key::Digit(8) => Some('['),
+				Key::Digit(9) => Some(']'),
+				Key::Digit(0) => Some('}'),
+				Key::NonUsBackslash => Some('|'),
+				_ => None,
+			};
+		}
 		
-		if is_shift {
-			if raw_keycode >= Scancode::A as u32 && raw_keycode <= Scancode::Z as u32 {
-				let mut offset = raw_keycode - Scancode::A as u32;
-				//Hacky way of applying the German YZ swap code:
-				if offset == ('z' as u32 - 'a' as u32) {
-					offset = 'y' as u32 - 'a' as u32;
-				} else if offset == ('y' as u32 - 'a' as u32) {
-					offset = 'z' as u32 - 'a' as u32;
-				}
-				return Some(char::from_u32(offset + 'A' as u32).expect("This should not fail"));
-			}
-			match keycode {
-				Scancode::Space => Some(' '),
+		if let Key::Letter(letter) = key {
+			//Hacky way of applying the German YZ swap code:
+			let letter = match letter {
+				'y' => 'z',
+				'z' => 'y',
+				_ => *letter,
+			};
+			return Some(if modifiers.shift { letter.to_ascii_uppercase() } else { letter });
+		}
+		
+		if modifiers.shift {
+			match key {
+				Key::Space => Some(' '),
 				
-				Scancode::Num1 => Some('!'),
-				Scancode::Num2 => Some('"'),
-				Scancode::Num7 => Some('/'),
-				Scancode::Num8 => Some('('),
-				Scancode::Num9 => Some(')'),
-				Scancode::Num0 => Some('='),
+				Key::Digit(1) => Some('!'),
+				Key::Digit(2) => Some('"'),
+				Key::Digit(4) => Some('$'),
+				Key::Digit(5) => Some('%'),
+				Key::Digit(6) => Some('&'),
+				Key::Digit(7) => Some('/'),
+				Key::Digit(8) => Some('('),
+				Key::Digit(9) => Some(')'),
+				Key::Digit(0) => Some('='),
 				
-				Scancode::Period => Some(':'),
-				Scancode::Backslash => Some('\''),
-				Scancode::RightBracket => Some('*'),
+				Key::Comma => Some(';'),
+				Key::Period => Some(':'),
+				Key::Slash => Some('_'),
+				Key::Backslash => Some('\''),
+				Key::RightBracket => Some('*'),
+				Key::NonUsBackslash => Some('>'),
 				
-				Scancode::Minus => Some('?'),
+				Key::Minus => Some('?'),
 				_ => {
-					// println!("Uff: {:x?}", keycode);
+					// println!("Uff: {:x?}", key);
 					None
 				}
 			}
 		} else {
-			if raw_keycode >= Scancode::A as u32 && raw_keycode <= Scancode::Z as u32 {
-				let mut offset = raw_keycode - Scancode::A as u32;
-				//Hacky way of applying the German YZ swap code:
-				if offset == ('z' as u32 - 'a' as u32) {
-					offset = 'y' as u32 - 'a' as u32;
-				} else if offset == ('y' as u32 - 'a' as u32) {
-					offset = 'z' as u32 - 'a' as u32;
-				}
-				return Some(char::from_u32(offset + 'a' as u32).expect("This should not fail"));
-			} else if raw_keycode >= Scancode::Num1 as u32 && raw_keycode <= Scancode::Num9 as u32 {
-				let offset = raw_keycode - Scancode::Num1 as u32;
-				return Some(char::from_u32(offset + '1' as u32).expect("This should not fail"));
-			}
-			match keycode {
-				Scancode::Space => Some(' '),
+			match key {
+				Key::Space => Some(' '),
 				
-				Scancode::Num0 => Some('0'),
+				Key::Digit(digit) => char::from_digit(*digit as u32, 10),
 				
-				Scancode::Comma => Some(','),
-				Scancode::Period => Some('.'),
-				Scancode::Slash => Some('-'),
+				Key::Comma => Some(','),
+				Key::Period => Some('.'),
+				Key::Slash => Some('-'),
 				
-				Scancode::RightBracket => Some('+'),
+				Key::Backslash => Some('#'),
+				Key::RightBracket => Some('+'),
+				Key::NonUsBackslash => Some('<'),
 				_ => {
-					// println!("Uff: {:?}", keycode);
+					// println!("Uff: {:?}", key);
 					None
 				}
 			}
 		}
 	}
 }
+
+//Without wrapping or with word wrapping, rows are redrawn from the layout of the document, instead of the incremental drawing above.
+//Only the rows of changed lines get redrawn, unless the view had to scroll or the amount of rows changed.
+impl Typewriter {
+	fn type_letter_by_layout(&mut self, letter: &char, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		if self.memory.memory_full() {
+			//TODO: Bell.
+			return Ok(());
+		}
+		let (line, _) = self.cursor_line_and_column();
+		self.memory.insert(font.id(*letter));
+		self.dirty = true;
+		self.refresh_layout(line..line + 1, letter_instructions, font)
+	}
+	
+	fn handle_key_by_layout(&mut self, key: &Key, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		let (line, _) = self.cursor_line_and_column();
+		let changed_lines = match key {
+			Key::Backspace => {
+				if self.memory.cursor_at_beginning() {
+					//TODO: Bell.
+					return Ok(());
+				}
+				let removes_newline = self.memory.memory[self.memory.pointer_before_cursor - 1] == NEWLINE;
+				self.memory.delete_backwards();
+				self.dirty = true;
+				if removes_newline {
+					//Lines got joined, everything below moves up:
+					line - 1..usize::MAX
+				} else {
+					line..line + 1
+				}
+			}
+			Key::Return => {
+				if self.memory.memory_full() {
+					//TODO: Bell
+					return Ok(());
+				}
+				self.memory.insert(NEWLINE);
+				self.dirty = true;
+				//Line got split, everything below moves down:
+				line..usize::MAX
+			}
+			Key::Left => {
+				if self.memory.cursor_at_beginning() {
+					//TODO: Bell.
+					return Ok(());
+				}
+				self.memory.move_after_cursor();
+				0..0
+			}
+			Key::Right => {
+				if self.memory.cursor_at_end() {
+					//TODO: Bell.
+					return Ok(());
+				}
+				self.memory.move_before_cursor();
+				0..0
+			}
+			_ => return Ok(()),
+		};
+		self.refresh_layout(changed_lines, letter_instructions, font)
+	}
+	
+	///Lays out the document once after it changed, then redraws what moved or changed.
+	fn refresh_layout(&mut self, changed_lines: Range<usize>, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		let contents = self.memory.contents();
+		let rows = visual_rows(&contents, self.wrap_mode, self.grid.char_width);
+		let previous_row_count = std::mem::replace(&mut self.layout, rows).len();
+		let lines = if self.update_layout_view() {
+			//The view scrolled, everything on the canvas moved:
+			0..usize::MAX
+		} else if self.layout.len() != previous_row_count {
+			//Rows got added or removed, everything below the change moved:
+			changed_lines.start..usize::MAX
+		} else {
+			changed_lines
+		};
+		self.redraw_layout_lines(&contents, lines, letter_instructions, font)
+	}
+	
+	fn cursor_line_and_column(&self) -> (usize, usize) {
+		line_and_column(&self.memory.memory[..self.memory.pointer_before_cursor])
+	}
+	
+	///Scrolls the view just enough to contain the cursor and places the cursor on the canvas. Returns true if the view scrolled.
+	fn update_layout_view(&mut self) -> bool {
+		let rows = &self.layout;
+		let row = cursor_row(rows, self.memory.pointer_before_cursor, self.grid.char_width);
+		let column = self.memory.pointer_before_cursor - rows[row].start;
+		let previous_view = (self.view_row, self.view_column);
+		if row < self.view_row {
+			self.view_row = row;
+		} else if row >= self.view_row + self.grid.char_height {
+			self.view_row = row + 1 - self.grid.char_height;
+		}
+		//Only without wrapping the cursor can leave the canvas horizontally:
+		if self.wrap_mode == WrapMode::NoWrap {
+			if column < self.view_column {
+				self.view_column = column;
+			} else if column >= self.view_column + self.grid.char_width {
+				self.view_column = column + 1 - self.grid.char_width;
+			}
+		}
+		self.cursor.x = column - self.view_column;
+		self.cursor.y = row - self.view_row;
+		previous_view != (self.view_row, self.view_column)
+	}
+	
+	///Redraws all canvas rows showing one of the lines. Without wrapping, rows that continue outside of the view get a marker at that end.
+	fn redraw_layout_lines(&self, contents: &[u8], lines: Range<usize>, letter_instructions: &mut Vec<LetterInstruction>, font: &Font) -> Result<(), Error> {
+		let left_marker = font.id('←');
+		let right_marker = font.id('→');
+		let rows = &self.layout;
+		for y in 0..self.grid.char_height {
+			let index = self.view_row + y;
+			let text = match rows.get(index) {
+				Some(row) => {
+					if !lines.contains(&row.line) {
+						continue;
+					}
+					//The row ends where the next one starts, but a newline is not part of either row:
+					let end = rows.get(index + 1)
+						.map(|next| if next.continuation { next.start } else { next.start - 1 })
+						.unwrap_or(contents.len());
+					&contents[row.start..end]
+				}
+				None => {
+					//Rows after the document only change, when everything below a line is redrawn:
+					if lines.end != usize::MAX {
+						continue;
+					}
+					&contents[..0]
+				}
+			};
+			for x in 0..self.grid.char_width {
+				let column = self.view_column + x;
+				let id = if x == 0 && self.view_column > 0 && !text.is_empty() {
+					left_marker
+				} else if self.wrap_mode == WrapMode::NoWrap && x == self.grid.char_width_upper_bound() && text.len() > column + 1 {
+					right_marker
+				} else {
+					text.get(column).copied().unwrap_or(0)
+				};
+				letter_instructions.push(LetterInstruction {
+					pos_x: x,
+					pos_y: y,
+					id,
+				});
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,48 @@
+use std::fs::metadata;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::font::{load_font_file, Font, FontError};
+
+//How often the font file is checked for changes:
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+///Notices changes of the font file by polling its modification time, times come from a clock (see clock.rs).
+pub struct FontWatcher {
+	path: PathBuf,
+	modified: Option<SystemTime>,
+	next_check: Duration,
+}
+
+impl FontWatcher {
+	///Expects the font to be loaded already, only later changes are reported.
+	pub fn new(path: &Path, now: Duration) -> Self {
+		Self {
+			path: path.to_path_buf(),
+			modified: modified(path),
+			next_check: now + POLL_INTERVAL,
+		}
+	}
+	
+	pub fn until_check(&self, now: Duration) -> Duration {
+		self.next_check.saturating_sub(now)
+	}
+	
+	///Loads the font again if the file changed since the last check, None if it is time to check or nothing changed.
+	///A file which is missing or cannot be loaded is reported once, until it changes again.
+	pub fn poll(&mut self, now: Duration) -> Option<Result<Font, FontError>> {
+		if now < self.next_check {
+			return None;
+		}
+		self.next_check = now + POLL_INTERVAL;
+		let modified = modified(&self.path);
+		if modified == self.modified {
+			return None;
+		}
+		self.modified = modified;
+		Some(load_font_file(&self.path))
+	}
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+	metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
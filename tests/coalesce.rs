@@ -0,0 +1,48 @@
+use typewriter::coalesce::{CoalesceStats, Coalescer};
+use typewriter::config::GridLayout;
+use typewriter::typewriter::LetterInstruction;
+
+fn letter(pos_x: usize, pos_y: usize, id: u8) -> LetterInstruction {
+	LetterInstruction { pos_x, pos_y, id }
+}
+
+fn positions(letter_instructions: &[LetterInstruction]) -> Vec<(usize, usize, u8)> {
+	letter_instructions.iter().map(|instruction| (instruction.pos_x, instruction.pos_y, instruction.id)).collect()
+}
+
+#[test]
+fn keeps_only_the_final_changed_letter_per_slot() {
+	let grid = GridLayout::with_size(4, 2).expect("Grid should be valid.");
+	let mut coalescer = Coalescer::new(&grid);
+	
+	//Clear then draw, a row cleared and drawn again, and a space onto an empty slot:
+	let mut letter_instructions = vec![letter(0, 0, 0), letter(0, 0, 5), letter(1, 1, 7), letter(1, 1, 0), letter(1, 1, 8), letter(2, 0, 0)];
+	coalescer.coalesce(&mut letter_instructions);
+	assert_eq!(positions(&letter_instructions), [(0, 0, 5), (1, 1, 8)]);
+	
+	//Redrawing what is already shown draws nothing, the status line is part of the canvas:
+	let mut letter_instructions = vec![letter(0, 0, 0), letter(0, 0, 5), letter(3, 2, 9)];
+	coalescer.coalesce(&mut letter_instructions);
+	assert_eq!(positions(&letter_instructions), [(3, 2, 9)]);
+	assert_eq!(coalescer.stats, CoalesceStats { received: 9, drawn: 3 });
+	assert_eq!(coalescer.stats.saved(), 6);
+	
+	//Outside of the canvas is passed on, so that it gets reported:
+	let mut letter_instructions = vec![letter(4, 0, 1), letter(0, 3, 1)];
+	coalescer.coalesce(&mut letter_instructions);
+	assert_eq!(positions(&letter_instructions), [(4, 0, 1), (0, 3, 1)]);
+}
+
+#[test]
+fn reset_forgets_the_canvas_but_not_the_statistics() {
+	let grid = GridLayout::with_size(4, 2).expect("Grid should be valid.");
+	let mut coalescer = Coalescer::new(&grid);
+	let mut letter_instructions = vec![letter(0, 0, 5)];
+	coalescer.coalesce(&mut letter_instructions);
+	
+	coalescer.reset(&GridLayout::with_size(6, 3).expect("Grid should be valid."));
+	let mut letter_instructions = vec![letter(0, 0, 5), letter(5, 3, 2)];
+	coalescer.coalesce(&mut letter_instructions);
+	assert_eq!(positions(&letter_instructions), [(0, 0, 5), (5, 3, 2)]);
+	assert_eq!(coalescer.stats, CoalesceStats { received: 3, drawn: 3 });
+}
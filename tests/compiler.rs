@@ -0,0 +1,113 @@
+use typewriter::compiler::{compile_font, compile_glyph, parse_ascii_glyphs, parse_pbm, CompileError};
+use typewriter::font;
+use typewriter::font::format_symbol;
+use typewriter::glyph::rasterize;
+
+const GLYPHS: &str = "
+key T
+#####
+..#..
+..#..
+..#..
+..#..
+..#..
+..#..
+.....
+.....
+.....
+.....
+
+key o
+.....
+.....
+.....
+.###.
+#...#
+#...#
+#...#
+.###.
+.....
+.....
+.....
+";
+
+#[test]
+fn built_in_symbols_compile_to_at_most_as_many_instructions() {
+	let font = font::load_font().expect("Could not load font.");
+	let (mut built_in, mut compiled) = (0, 0);
+	for (key, symbol) in &font.symbols_by_key {
+		if *key == ' ' {
+			continue;
+		}
+		let bitmap = rasterize(symbol);
+		let instructions = compile_glyph(&bitmap).expect("Symbols are not empty.");
+		assert_eq!(rasterize(&instructions), bitmap, "Compiled '{}' draws something else", key);
+		assert!(instructions.len() <= symbol.len(), "Compiled '{}' needs {} instructions, the font only {}", key, instructions.len(), symbol.len());
+		built_in += symbol.len();
+		compiled += instructions.len();
+	}
+	println!("Built-in font has {} instructions, compiled {}", built_in, compiled);
+}
+
+#[test]
+fn encoded_symbols_load_again() {
+	let font = font::load_font().expect("Could not load font.");
+	let text = font.keys_by_id.iter()
+		.filter(|(id, _)| **id != 0)
+		.map(|(_, key)| format_symbol(*key, &font.symbols_by_key[key]))
+		.collect::<String>();
+	let loaded = font::parse_font(&text, "Encoded.txt").expect("Encoded font should load.");
+	for (key, symbol) in &font.symbols_by_key {
+		assert_eq!(&loaded.symbols_by_key[key], symbol, "Symbol '{}' changed", key);
+	}
+}
+
+#[test]
+fn ascii_and_pbm_glyphs_compile() {
+	let glyphs = parse_ascii_glyphs(GLYPHS).expect("Glyphs should parse.");
+	let text = compile_font(&glyphs).expect("Glyphs should compile.");
+	//The T is two lines, the o a rectangle outline and the sides:
+	assert_eq!(text.lines().filter(|line| !line.starts_with("key ")).count(), 4, "Unexpected font:\n{}", text);
+	let font = font::parse_font(&text, "Compiled.txt").expect("Compiled font should load.");
+	assert_eq!(rasterize(&font.symbols_by_key[&'T']), glyphs[0].1);
+	
+	//The same glyphs as plain and raw image:
+	let rows = (0..11).map(|y| glyphs.iter().flat_map(|(_, bitmap)| bitmap[y]).collect::<Vec<bool>>()).collect::<Vec<_>>();
+	let mut plain = b"P1\n# Comment\n10 11\n".to_vec();
+	let mut raw = b"P4 10 11\n".to_vec();
+	for row in &rows {
+		plain.extend(row.iter().map(|pixel| if *pixel { b'1' } else { b'0' }));
+		plain.push(b'\n');
+		let bits = row.iter().enumerate().fold(0u16, |bits, (x, pixel)| bits | ((*pixel as u16) << (15 - x)));
+		raw.extend(bits.to_be_bytes());
+	}
+	assert_eq!(parse_pbm(&plain, "To").expect("Plain image should parse."), glyphs);
+	assert_eq!(parse_pbm(&raw, "To").expect("Raw image should parse."), glyphs);
+	assert_eq!(parse_pbm(&raw, "T"), Err(CompileError::WrongKeyCount { expected: 2, actual: 1 }));
+}
+
+#[test]
+fn invalid_glyphs_are_reported() {
+	assert_eq!(parse_ascii_glyphs("key A\n##.#x\n"), Err(CompileError::InvalidLine(2, "##.#x".to_string())));
+	assert_eq!(parse_ascii_glyphs("#####\n"), Err(CompileError::InvalidLine(1, "#####".to_string())));
+	assert_eq!(parse_ascii_glyphs("key A\n#####\n"), Err(CompileError::WrongRowCount('A', 1)));
+	
+	let glyphs = parse_ascii_glyphs(GLYPHS).expect("Glyphs should parse.");
+	let empty = [[false; 5]; 11];
+	assert_eq!(compile_font(&[('x', empty)]), Err(CompileError::EmptyGlyph('x')));
+	assert_eq!(compile_font(&[glyphs[0], glyphs[0]]), Err(CompileError::DuplicateKey('T')));
+	assert!(matches!(compile_font(&[(' ', glyphs[0].1)]), Err(CompileError::Font(_))));
+}
+
+#[test]
+fn holes_are_carved_out_of_filled_areas() {
+	let glyphs = parse_ascii_glyphs("key H\n#####\n#####\n#####\n##.##\n#####\n#####\n#####\n.....\n.....\n.....\n.....\n").expect("Glyph should parse.");
+	let instructions = compile_glyph(&glyphs[0].1).expect("Glyph is not empty.");
+	//A rectangle and clearing the hole, instead of drawing around it:
+	assert_eq!(instructions.len(), 2);
+	assert!(!instructions[0].clear && instructions[1].clear);
+	
+	let text = compile_font(&glyphs).expect("Glyph should compile.");
+	let font = font::parse_font(&text, "Carved.txt").expect("Carved font should load.");
+	assert_eq!(rasterize(&font.symbols_by_key[&'H']), glyphs[0].1);
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+use typewriter::font;
+use typewriter::font::{format_symbol, FontError, Instruction};
+use typewriter::glyph::rasterize;
+
+#[test]
+fn built_in_font_matches_font_file() {
+	let built_in = font::load_font().expect("Could not load built-in font.");
+	let file = font::load_font_file(Path::new("FontBytes.txt")).expect("Could not load font file.");
+	assert_eq!(built_in.highest_id, file.highest_id);
+	assert_eq!(built_in.ids_by_key, file.ids_by_key);
+	
+	let missing = font::load_font_file(Path::new("Missing.txt")).err().expect("Missing file should fail.");
+	assert!(matches!(&missing, FontError::Read(message) if message.starts_with("Missing.txt: ")), "Unexpected error {:?}", missing);
+}
+
+#[test]
+fn errors_name_file_and_line() {
+	let text = std::fs::read_to_string("FontBytes.txt").expect("Could not read font file.");
+	let mut lines = text.lines().map(str::to_string).collect::<Vec<String>>();
+	
+	//Broken bit in line 5:
+	lines[4].replace_range(3..4, "2");
+	let error = font::parse_font(&lines.join("\n"), "Broken.txt").err().expect("Invalid character should fail.");
+	assert!(error.to_string().starts_with("Broken.txt:5: Font file line contains invalid character"), "Unexpected error '{}'", error);
+	//The status line only has room for a short form:
+	assert_eq!(error.summary(), "Font L5: invalid character");
+	
+	//Too short line 2:
+	let error = font::parse_font("000000000000000000\n0101\n", "Short.txt").err().expect("Short line should fail.");
+	assert_eq!(error.to_string(), "Short.txt:2: Font file has invalid line: '0101'");
+	
+	//Errors about the whole file have no line:
+	let error = font::parse_font(&text.lines().take(10).collect::<Vec<&str>>().join("\n"), "Cut.txt").err().expect("Missing symbols should fail.");
+	assert!(matches!(&error, FontError::InFile { line: None, error, .. } if matches!(**error, FontError::WrongSymbolCount { .. })), "Unexpected error {:?}", error);
+	assert!(error.summary().starts_with("Font: ") && error.summary().ends_with(" symbols"), "Unexpected summary '{}'", error.summary());
+	
+	//Windows line endings are fine:
+	assert!(font::parse_font(&text.replace('\n', "\r\n"), "Windows.txt").is_ok());
+}
+
+///Instruction lines of each symbol of the font file, in built-in order.
+fn symbol_blocks(text: &str) -> Vec<Vec<&str>> {
+	let mut blocks: Vec<Vec<&str>> = Vec::new();
+	for line in text.lines().filter(|line| line.contains('1')) {
+		//The start bit is the leftmost one:
+		if line.starts_with('1') {
+			blocks.push(Vec::new());
+		}
+		blocks.last_mut().expect("Font file starts with a symbol.").push(line);
+	}
+	blocks
+}
+
+#[test]
+fn key_lines_choose_and_order_symbols() {
+	let text = std::fs::read_to_string("FontBytes.txt").expect("Could not read font file.");
+	let blocks = symbol_blocks(&text);
+	let built_in = font::load_font().expect("Could not load built-in font.");
+	let block_of = |key: char| blocks[built_in.ids_by_key[&key] as usize - 1].join("\n");
+	
+	//Swapped, renamed and most symbols left out:
+	let annotated = format!("# Test font\nkey B\n{}\nkey A\n{}\n\nkey §\n{}\n", block_of('A'), block_of('B'), block_of('?'));
+	let font = font::parse_font(&annotated, "Keys.txt").expect("Annotated font should load.");
+	assert_eq!(font.highest_id, 3);
+	assert_eq!(font.keys_by_id[&1], 'B');
+	assert_eq!(font.keys_by_id[&3], '§');
+	assert!(!font.ids_by_key.contains_key(&'C'));
+	assert_eq!(rasterize(&font.symbols_by_key[&'B']), rasterize(&built_in.symbols_by_key[&'A']));
+	assert_eq!(rasterize(&font.symbols_by_key[&'§']), rasterize(&built_in.symbols_by_key[&'?']));
+	assert_eq!(rasterize(&font.symbols_by_key[&' ']), rasterize(&built_in.space));
+	
+	let after_a = block_of('A').lines().count() + 2;
+	let error = |text: String| font::parse_font(&text, "Keys.txt").err().expect("Font should fail.").to_string();
+	assert_eq!(error(format!("key A\n{}\n{}\n", block_of('A'), block_of('B'))), format!("Keys.txt:{}: Symbol has no key line, either all or no symbols need one.", after_a));
+	assert_eq!(error(format!("key A\n{}\nkey A\n{}\n", block_of('A'), block_of('B'))), format!("Keys.txt:{}: Key 'A' is used by more than one symbol.", after_a + 1));
+	assert_eq!(error(format!("key  \n{}\n", block_of('A'))), "Keys.txt:1: Key ' ' is reserved.");
+	assert_eq!(error(format!("key AB\n{}\n", block_of('A'))), "Keys.txt:1: Font file has invalid line: 'key AB'");
+	assert_eq!(error(format!("{}\nkey B\n", block_of('A'))), format!("Keys.txt:{}: Key line 'B' is not followed by the start of a symbol.", after_a - 1));
+	let (first, rest) = block_of('A').split_once('\n').map(|(first, rest)| (first.to_string(), rest.to_string())).expect("Symbol has more than one instruction.");
+	assert_eq!(error(format!("key A\n{}\nkey B\n{}\n", first, rest)), "Keys.txt:3: Key line 'B' is not followed by the start of a symbol.");
+}
+
+#[test]
+fn clear_instructions_carve_out_of_symbols() {
+	//Filled rectangle, then clearing a vertical line in the middle of it:
+	let fill = Instruction { clear: false, x_start: 0, x_fill: true, x_end: 4, y_start: 0, y_fill: true, y_end: 10 };
+	let carve = Instruction { clear: true, x_start: 2, x_fill: false, x_end: 2, y_start: 2, y_fill: true, y_end: 8 };
+	let text = format_symbol('#', &[fill, carve]);
+	assert!(text.lines().nth(2).expect("Symbol has two instructions.").ends_with('0'), "Clear instruction has the enable bit set:\n{}", text);
+	let font = font::parse_font(&text, "Clear.txt").expect("Font with clear instruction should load.");
+	assert_eq!(font.symbols_by_key[&'#'], vec![fill, carve]);
+	let bitmap = rasterize(&font.symbols_by_key[&'#']);
+	assert!(bitmap[0][2] && bitmap[1][2] && !bitmap[2][2] && !bitmap[8][2] && bitmap[9][2] && bitmap[5][1]);
+}
+
+#[test]
+fn built_in_ids_fit_into_seven_bits() {
+	let font = font::load_font().expect("Could not load font.");
+	assert!(font.highest_id < 0x80, "Highest ID {} does not fit the 7 bit charset", font.highest_id);
+	for key in ";#_[]{}<>&|%@$".chars() {
+		assert!(font.ids_by_key.contains_key(&key), "Missing symbol '{}'", key);
+	}
+}
+
+#[test]
+fn fonts_are_limited_to_seven_bits() {
+	let dot = [Instruction { clear: false, x_start: 2, x_fill: false, x_end: 2, y_start: 3, y_fill: false, y_end: 3 }];
+	let font_of = |count: u32| (0..count).map(|index| format_symbol(char::from_u32(0xC0 + index).unwrap(), &dot)).collect::<String>();
+	let font = font::parse_font(&font_of(127), "Full.txt").expect("127 symbols should fit.");
+	assert_eq!(font.highest_id, 0x7F);
+	let error = font::parse_font(&font_of(128), "Overfull.txt").err().expect("128 symbols should not fit.");
+	assert!(matches!(&error, FontError::InFile { error, .. } if matches!(**error, FontError::TooManySymbols(128))), "Unexpected error {:?}", error);
+}
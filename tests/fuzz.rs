@@ -0,0 +1,283 @@
+use typewriter::coalesce::Coalescer;
+use typewriter::config::{GridLayout, MEMORY_SIZE, NEWLINE};
+use typewriter::font;
+use typewriter::font::Font;
+use typewriter::input::{Key, KeyModifiers};
+use typewriter::screen::Screen;
+use typewriter::typewriter::{LetterInstruction, Typewriter};
+
+//Runs random keystrokes against the typewriter and compares it with a trivial model:
+// A plain vector with the cursor index, plus the first visible row to know where the cursor should be on the canvas.
+//The coalesced instructions also have to paint the same canvas as the original ones.
+
+const SEEDS: u64 = 200;
+const STEPS: usize = 400;
+
+struct Random(u64);
+
+impl Random {
+	fn next(&mut self) -> u64 {
+		//Xorshift, good enough to generate keystrokes:
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+	
+	fn below(&mut self, bound: usize) -> usize {
+		(self.next() % bound as u64) as usize
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Action {
+	Type(char),
+	Return,
+	Backspace,
+	Left,
+	Right,
+	Resize(usize, usize),
+}
+
+struct Model {
+	grid: GridLayout,
+	text: Vec<u8>,
+	cursor: usize,
+	top_row: usize,
+}
+
+impl Model {
+	fn new(grid: GridLayout) -> Self {
+		Self {
+			grid,
+			text: Vec::new(),
+			cursor: 0,
+			top_row: 0,
+		}
+	}
+	
+	fn apply(&mut self, action: Action, font: &Font) {
+		match action {
+			Action::Type(letter) => self.insert(*font.ids_by_key.get(&letter).unwrap()),
+			Action::Return => self.insert(NEWLINE),
+			Action::Backspace => {
+				if self.cursor > 0 {
+					self.cursor -= 1;
+					self.text.remove(self.cursor);
+				}
+			}
+			Action::Left => self.cursor = self.cursor.saturating_sub(1),
+			Action::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+			Action::Resize(char_width, char_height) => {
+				//The cursor keeps its canvas row, unless the canvas got too small or the document is too short:
+				let (_, y) = self.expected_cursor();
+				self.grid = GridLayout::with_size(char_width, char_height).unwrap();
+				let (_, row) = self.cursor_row();
+				self.top_row = row - y.min(char_height - 1).min(row);
+			}
+		}
+		//The canvas only scrolls as far as needed to keep the cursor visible:
+		let (_, row) = self.cursor_row();
+		if row < self.top_row {
+			self.top_row = row;
+		} else if row >= self.top_row + self.grid.char_height {
+			self.top_row = row + 1 - self.grid.char_height;
+		}
+	}
+	
+	fn insert(&mut self, id: u8) {
+		if self.text.len() < MEMORY_SIZE {
+			self.text.insert(self.cursor, id);
+			self.cursor += 1;
+		}
+	}
+	
+	///Column and visual row of the cursor, counted from the document start.
+	fn cursor_row(&self) -> (usize, usize) {
+		let mut row = 0;
+		let mut column = 0;
+		for value in &self.text[..self.cursor] {
+			if *value == NEWLINE {
+				row += 1;
+				column = 0;
+			} else {
+				column += 1;
+				if column > self.grid.char_width {
+					column = 1;
+					row += 1;
+				}
+			}
+		}
+		(column, row)
+	}
+	
+	fn expected_cursor(&self) -> (usize, usize) {
+		let (column, row) = self.cursor_row();
+		(column, row - self.top_row)
+	}
+	
+	///Text rows of the canvas, as a full repaint of the visible rows would draw them.
+	fn expected_rows(&self) -> Vec<Vec<u8>> {
+		let mut rows = vec![vec![0; self.grid.char_width]; self.grid.char_height];
+		let (mut column, mut row) = (0, 0usize);
+		for value in &self.text {
+			if *value == NEWLINE {
+				row += 1;
+				column = 0;
+				continue;
+			}
+			if column == self.grid.char_width {
+				column = 0;
+				row += 1;
+			}
+			if let Some(slots) = row.checked_sub(self.top_row).and_then(|y| rows.get_mut(y)) {
+				slots[column] = *value;
+			}
+			column += 1;
+		}
+		rows
+	}
+}
+
+fn random_action(random: &mut Random, letters: &[char], resizing: bool) -> Action {
+	if resizing && random.below(30) == 0 {
+		return Action::Resize(2 + random.below(50), 1 + random.below(12));
+	}
+	match random.below(20) {
+		0..=9 => Action::Type(letters[random.below(letters.len())]),
+		10..=11 => Action::Return,
+		12..=13 => Action::Backspace,
+		14..=16 => Action::Left,
+		_ => Action::Right,
+	}
+}
+
+fn perform(typewriter: &mut Typewriter, action: Action, font: &Font) -> Vec<LetterInstruction> {
+	let mut letter_instructions: Vec<LetterInstruction> = Vec::new();
+	let result = match action {
+		Action::Type(letter) => typewriter.type_letter(&letter, &mut letter_instructions, font),
+		Action::Return => typewriter.handle_input(&Key::Return, &KeyModifiers::NONE, &mut letter_instructions, font),
+		Action::Backspace => typewriter.handle_input(&Key::Backspace, &KeyModifiers::NONE, &mut letter_instructions, font),
+		Action::Left => typewriter.handle_input(&Key::Left, &KeyModifiers::NONE, &mut letter_instructions, font),
+		Action::Right => typewriter.handle_input(&Key::Right, &KeyModifiers::NONE, &mut letter_instructions, font),
+		Action::Resize(char_width, char_height) => {
+			let grid = GridLayout::with_size(char_width, char_height).unwrap();
+			typewriter.set_grid(grid, &mut letter_instructions, font)
+		}
+	};
+	if let Err(error) = result {
+		panic!("Typewriter failed on {:?}: {}", action, error);
+	}
+	letter_instructions
+}
+
+///Two canvases, one painted with every instruction and one only with the coalesced ones.
+struct Canvases {
+	screen: Screen,
+	coalesced_screen: Screen,
+	coalescer: Coalescer,
+}
+
+impl Canvases {
+	fn new(grid: &GridLayout) -> Self {
+		Self {
+			screen: Screen::new(grid),
+			coalesced_screen: Screen::new(grid),
+			coalescer: Coalescer::new(grid),
+		}
+	}
+	
+	fn apply(&mut self, mut letter_instructions: Vec<LetterInstruction>, history: &[Action]) {
+		self.screen.apply(&letter_instructions).expect("Instruction outside of canvas.");
+		let received = letter_instructions.len();
+		self.coalescer.coalesce(&mut letter_instructions);
+		assert!(letter_instructions.len() <= received, "Coalescing added instructions after {:?}", history.last());
+		self.coalesced_screen.apply(&letter_instructions).expect("Coalesced instruction outside of canvas.");
+		assert_eq!(self.coalesced_screen.cells, self.screen.cells, "Coalesced canvas diverged after {} actions, last ones: {:?}", history.len(), &history[history.len().saturating_sub(10)..]);
+	}
+}
+
+fn check(typewriter: &Typewriter, model: &Model, screen: &Screen, history: &[Action]) {
+	let describe = || format!("after {} actions, last ones: {:?}", history.len(), &history[history.len().saturating_sub(10)..]);
+	assert_eq!(typewriter.memory.contents(), model.text, "Memory diverged {}", describe());
+	assert_eq!(typewriter.memory.pointer_before_cursor, model.cursor, "Memory cursor diverged {}", describe());
+	assert_eq!(typewriter.cursor.get_draw_cursor_position(), model.expected_cursor(), "Canvas cursor diverged {}", describe());
+	assert_eq!(screen.cells[..model.grid.char_height], model.expected_rows(), "Canvas diverged from a full repaint {}", describe());
+}
+
+fn run(grid: GridLayout, seed: u64, prefix: &[Action], steps: usize, resizing: bool, font: &Font) {
+	//In a fixed order, so that a seed always types the same letters:
+	let mut letters = font.ids_by_key.keys().copied().collect::<Vec<char>>();
+	letters.sort();
+	let mut random = Random(seed);
+	let mut typewriter = Typewriter::new(grid);
+	let mut model = Model::new(grid);
+	let mut canvases = Canvases::new(&grid);
+	let mut history = Vec::new();
+	for action in prefix.iter().copied().chain((0..steps).map(|_| random_action(&mut random, &letters, resizing))) {
+		history.push(action);
+		let letter_instructions = perform(&mut typewriter, action, font);
+		if let Action::Resize(..) = action {
+			//The canvas is replaced, just like the window buffer:
+			canvases = Canvases::new(&typewriter.grid);
+		}
+		canvases.apply(letter_instructions, &history);
+		model.apply(action, font);
+		check(&typewriter, &model, &canvases.screen, &history);
+	}
+}
+
+#[test]
+fn random_editing() {
+	let font = font::load_font().expect("Could not load font.");
+	for seed in 1..=SEEDS {
+		run(GridLayout::default(), seed, &[], STEPS, false, &font);
+	}
+}
+
+#[test]
+fn random_editing_at_canvas_end() {
+	let font = font::load_font().expect("Could not load font.");
+	//Fill the canvas completely, so that every further letter scrolls:
+	let grid = GridLayout::default();
+	let prefix = vec![Action::Type('x'); grid.char_width * grid.char_height];
+	for seed in 1..=SEEDS / 10 {
+		run(grid, seed, &prefix, STEPS, false, &font);
+	}
+}
+
+#[test]
+fn random_editing_with_full_memory() {
+	let font = font::load_font().expect("Could not load font.");
+	let mut prefix = Vec::new();
+	for index in 0..MEMORY_SIZE - 8 {
+		prefix.push(if index % 30 == 29 { Action::Return } else { Action::Type('m') });
+	}
+	for seed in 1..=SEEDS / 20 {
+		run(GridLayout::default(), seed, &prefix, STEPS, false, &font);
+	}
+}
+
+#[test]
+fn random_editing_on_small_grids() {
+	let font = font::load_font().expect("Could not load font.");
+	//Tiny grids wrap and scroll all the time:
+	for (char_width, char_height) in [(2, 1), (3, 2), (7, 3)] {
+		let grid = GridLayout::with_size(char_width, char_height).expect("Grid should be valid.");
+		for seed in 1..=SEEDS / 4 {
+			run(grid, seed, &[], STEPS, false, &font);
+		}
+		//Short documents starting with an empty line, to often move back to the document start:
+		for seed in 1..=SEEDS {
+			run(grid, seed, &[Action::Return], 10, false, &font);
+		}
+	}
+}
+
+#[test]
+fn random_editing_with_resizing() {
+	let font = font::load_font().expect("Could not load font.");
+	for seed in 1..=SEEDS / 2 {
+		run(GridLayout::default(), seed, &[], STEPS, true, &font);
+	}
+}
@@ -0,0 +1,121 @@
+use typewriter::config::REPLACEMENT;
+use typewriter::font;
+use typewriter::font::Instruction;
+use typewriter::glyph::{areas, rasterize, Area, Bitmap, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+fn rows(bitmap: &Bitmap) -> Vec<String> {
+	bitmap.iter()
+		.map(|row| row.iter().map(|pixel| if *pixel { '#' } else { '.' }).collect())
+		.collect()
+}
+
+fn instruction(x_start: u32, x_end: u32, x_fill: bool, y_start: u32, y_end: u32, y_fill: bool, clear: bool) -> Instruction {
+	Instruction {
+		clear,
+		x_start,
+		x_fill,
+		x_end,
+		y_start,
+		y_fill,
+		y_end,
+	}
+}
+
+#[test]
+fn space_is_empty_and_symbols_are_not() {
+	let font = font::load_font().expect("Could not load font.");
+	assert_eq!(rasterize(&font.space), [[false; GLYPH_WIDTH]; GLYPH_HEIGHT]);
+	for (key, symbol) in &font.symbols_by_key {
+		if *key != ' ' {
+			assert!(rasterize(symbol).iter().flatten().any(|pixel| *pixel), "Symbol '{}' draws nothing", key);
+		}
+	}
+}
+
+#[test]
+fn instructions_draw_points_lines_and_clear() {
+	let bitmap = rasterize(&[
+		//Two horizontal lines at the bottom and top, but only from X 1 to 3:
+		instruction(1, 3, true, 0, 10, false, false),
+		//Single point in the middle, then cleared again from an overlapping vertical line:
+		instruction(2, 2, false, 5, 5, false, false),
+		instruction(2, 2, false, 10, 4, true, true),
+		//Four corner points:
+		instruction(0, 4, false, 2, 3, false, false),
+	]);
+	assert_eq!(rows(&bitmap), [
+		".#.#.", //Top line, cleared in the middle.
+		".....",
+		".....",
+		".....",
+		".....",
+		".....",
+		".....",
+		"#...#",
+		"#...#",
+		".....",
+		".###.",
+	]);
+}
+
+#[test]
+fn rectangles_fill_only_their_rows() {
+	//Rectangle from Y 2 to 4, which is not as high as the glyph. Its top is at the row of Y 4, in any order of start and end:
+	let expected = [Area { x: 1, y: 6, width: 3, height: 3 }];
+	assert_eq!(areas(&instruction(1, 3, true, 2, 4, true, false)), expected);
+	assert_eq!(areas(&instruction(3, 1, true, 4, 2, true, false)), expected);
+	assert_eq!(rows(&rasterize(&[instruction(3, 1, true, 4, 2, true, false)])), [
+		".....",
+		".....",
+		".....",
+		".....",
+		".....",
+		".....",
+		".###.",
+		".###.",
+		".###.",
+		".....",
+		".....",
+	]);
+}
+
+#[test]
+fn direct_drawing_matches_the_atlas() {
+	//Filling the areas of each instruction, like letters are drawn directly onto the canvas, has to give the same pixels as the atlas:
+	let font = font::load_font().expect("Could not load font.");
+	let reversed = vec![
+		instruction(4, 0, true, 10, 0, true, false),
+		instruction(3, 1, true, 8, 2, false, true),
+		instruction(4, 2, false, 6, 1, true, true),
+		instruction(3, 0, false, 9, 4, false, true),
+	];
+	for symbol in font.symbols_by_key.values().chain([&font.replacement, &reversed]) {
+		let mut bitmap = [[false; GLYPH_WIDTH]; GLYPH_HEIGHT];
+		for operation in symbol {
+			for area in areas(operation) {
+				for y in area.y..area.y + area.height as i32 {
+					for x in area.x..area.x + area.width as i32 {
+						bitmap[y as usize][x as usize] = !operation.clear;
+					}
+				}
+			}
+		}
+		assert_eq!(rows(&bitmap), rows(&rasterize(symbol)));
+	}
+}
+
+#[test]
+fn unknown_ids_use_the_replacement_symbol() {
+	let font = font::load_font().expect("Could not load font.");
+	let replacement = rasterize(&font.replacement);
+	//A box, with every other pixel inside cleared:
+	assert!(replacement[0].iter().all(|pixel| *pixel) && replacement[7].iter().all(|pixel| *pixel));
+	assert_eq!(replacement[1], [true, false, true, false, true]);
+	assert_eq!(replacement[2], [true, true, false, true, true]);
+	assert!(replacement[8..].iter().flatten().all(|pixel| !pixel));
+	
+	assert_eq!(font.id('§'), REPLACEMENT);
+	assert_eq!(font.symbol(REPLACEMENT), &font.replacement);
+	assert_eq!(font.symbol(font.highest_id + 1), &font.replacement);
+	assert_eq!(font.symbol(font.id('A')), &font.symbols_by_key[&'A']);
+}
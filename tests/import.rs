@@ -0,0 +1,132 @@
+use typewriter::font;
+use typewriter::glyph::{rasterize, Bitmap};
+use typewriter::import::{fit_glyphs, import_font, parse_bitmap_font, Fit, ImportError, SourceFont};
+
+//Font with the size of the display glyph, 3 rows below the baseline. The 'g' has a smaller bounding box, which has to be placed:
+const BDF: &str = "STARTFONT 2.1
+FONT -test-small
+SIZE 11 75 75
+FONTBOUNDINGBOX 5 11 0 -3
+CHARS 3
+STARTCHAR T
+ENCODING 84
+BBX 5 7 0 1
+BITMAP
+F8
+20
+20
+20
+20
+20
+20
+ENDCHAR
+STARTCHAR g
+ENCODING 103
+BBX 4 7 0 -3
+BITMAP
+70
+90
+90
+70
+10
+10
+E0
+ENDCHAR
+STARTCHAR unmapped
+ENCODING -1
+BBX 5 1 0 0
+BITMAP
+F8
+ENDCHAR
+ENDFONT
+";
+
+fn bitmap(rows: [&str; 11]) -> Bitmap {
+	rows.map(|row| {
+		let mut pixels = [false; 5];
+		for (pixel, c) in pixels.iter_mut().zip(row.chars()) {
+			*pixel = c == '#';
+		}
+		pixels
+	})
+}
+
+///PSF 2 font of 8x16 glyphs with unicode table, each glyph given as rows from the top.
+fn psf2(glyphs: &[(char, Vec<u8>)]) -> Vec<u8> {
+	let mut bytes = vec![0x72, 0xb5, 0x4a, 0x86];
+	for field in [0, 32, 1, glyphs.len() as u32, 16, 16, 8] {
+		bytes.extend(u32::to_le_bytes(field));
+	}
+	for (_, rows) in glyphs {
+		bytes.extend(rows);
+	}
+	for (key, _) in glyphs {
+		bytes.extend(key.to_string().as_bytes());
+		bytes.push(0xFF);
+	}
+	bytes
+}
+
+#[test]
+fn bdf_glyphs_are_placed_at_the_baseline() {
+	let source = parse_bitmap_font(BDF.as_bytes()).expect("Font should parse.");
+	assert_eq!((source.width, source.height, source.ascent), (5, 11, 8));
+	assert_eq!(source.glyphs.len(), 2);
+	
+	let glyphs = fit_glyphs(&source, &['T', 'g', 'x'], Fit::Scale);
+	assert_eq!(glyphs, vec![
+		('T', bitmap(["#####", "..#", "..#", "..#", "..#", "..#", "..#", "", "", "", ""])),
+		('g', bitmap(["", "", "", "", ".###", "#..#", "#..#", ".###", "...#", "...#", "###."])),
+	]);
+	
+	let built_in = font::load_font().expect("Could not load built-in font.");
+	let text = import_font(&source, "small.bdf", &built_in, Fit::Scale).expect("Font should import.");
+	assert!(text.starts_with("# Imported from small.bdf, 5x11 pixels, fit by scaling\n# T = U+0054 T\n# g = U+0067 g\n# Missing: ABCDEF"), "Unexpected header:\n{}", text);
+	let imported = font::parse_font(&text, "Imported.txt").expect("Imported font should load.");
+	assert_eq!(rasterize(&imported.symbols_by_key[&'g']), glyphs[1].1);
+	assert_eq!(imported.ids_by_key.len(), 3);
+}
+
+#[test]
+fn big_psf_glyphs_are_scaled_or_cropped() {
+	//An 'H' 7 pixels wide and 11 high, standing on row 12, and a bar 3 rows below it:
+	let mut h = vec![0; 16];
+	for row in &mut h[2..13] {
+		*row = 0b1000_0010;
+	}
+	h[7] = 0b1111_1110;
+	let mut bar = vec![0; 16];
+	bar[15] = 0b0011_1000;
+	let source = parse_bitmap_font(&psf2(&[('H', h), ('_', bar)])).expect("Font should parse.");
+	//The baseline is guessed from the bottom of the 'H':
+	assert_eq!((source.width, source.height, source.ascent), (8, 16, 13));
+	
+	let scaled = fit_glyphs(&source, &['H', '_'], Fit::Scale);
+	assert_eq!(scaled, vec![
+		('H', bitmap(["#...#", "#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#", "", "", ""])),
+		('_', bitmap(["", "", "", "", "", "", "", "", "", "", ".##"])),
+	]);
+	let cropped = fit_glyphs(&source, &['H', '_'], Fit::Crop);
+	assert_eq!(cropped, vec![
+		//Only the bar is left of the 'H', its sides and top are cut off:
+		('H', bitmap(["", "", "#####", "", "", "", "", "", "", "", ""])),
+		('_', bitmap(["", "", "", "", "", "", "", "", "", "", ".###"])),
+	]);
+}
+
+#[test]
+fn invalid_fonts_are_reported() {
+	assert_eq!(parse_bitmap_font(b"P1 5 11"), Err(ImportError::UnknownFormat));
+	assert_eq!(parse_bitmap_font(b"STARTFONT 2.1\nENDFONT\n"), Err(ImportError::InvalidBdf(2, "FONTBOUNDINGBOX is missing".to_string())));
+	let broken = BDF.replace("E0\n", "X0\n");
+	assert_eq!(parse_bitmap_font(broken.as_bytes()), Err(ImportError::InvalidBdf(28, "BITMAP row is not hexadecimal".to_string())));
+	assert!(matches!(parse_bitmap_font(&psf2(&[('H', vec![0; 16])])[..40]), Err(ImportError::InvalidPsf(_))));
+	
+	//PSF 1 without unicode table, glyph numbers are the character codes:
+	let mut psf1 = vec![0x36, 0x04, 0x00, 8];
+	psf1.extend(vec![0; 256 * 8]);
+	psf1[4 + 'A' as usize * 8 + 3] = 0xFF;
+	let source: SourceFont = parse_bitmap_font(&psf1).expect("Font should parse.");
+	assert_eq!(source.glyphs[&'A'].name, "glyph 65");
+	assert!(source.glyphs[&'A'].pixels[3].iter().all(|pixel| *pixel));
+}
@@ -0,0 +1,76 @@
+use typewriter::font;
+use typewriter::font::{format_symbol, Instruction};
+use typewriter::lint::{lint_font, Finding, Severity, INSTRUCTION_BUDGET};
+
+fn instruction(x_start: u32, x_end: u32, x_fill: bool, y_start: u32, y_end: u32, y_fill: bool) -> Instruction {
+	Instruction {
+		clear: false,
+		x_start,
+		x_fill,
+		x_end,
+		y_start,
+		y_fill,
+		y_end,
+	}
+}
+
+fn rules(findings: &[Finding]) -> Vec<(Option<usize>, Option<char>, &'static str)> {
+	findings.iter().map(|finding| (finding.line, finding.key, finding.rule)).collect()
+}
+
+#[test]
+fn built_in_font_is_clean() {
+	let symbols = font::parse_font_symbols(font::DEFAULT_FONT, font::DEFAULT_FONT_NAME).expect("Could not load font.");
+	let findings = lint_font(&symbols, INSTRUCTION_BUDGET);
+	assert!(findings.is_empty(), "Built-in font has findings:\n{}", findings.iter().map(|finding| finding.to_string()).collect::<Vec<_>>().join("\n"));
+}
+
+#[test]
+fn mistakes_are_found() {
+	let bar = instruction(2, 2, false, 3, 10, true);
+	let text = [
+		//Line 1-3: Second instruction is already drawn by the first one:
+		format_symbol('A', &[bar, instruction(2, 2, false, 5, 5, false)]),
+		//Line 4-5: Reversed rectangle, which is drawn fine but not in canonical order:
+		format_symbol('B', &[instruction(4, 0, true, 3, 10, true)]),
+		//Line 6-7: Same as A, but without the redundant instruction:
+		format_symbol('C', &[bar]),
+		//Line 8-9: Below the baseline:
+		format_symbol('D', &[instruction(0, 4, true, 0, 0, false)]),
+		//Line 10-11: Also below the baseline, but expected:
+		format_symbol('g', &[instruction(0, 4, true, 0, 3, true)]),
+		//Line 12-15: Over budget:
+		format_symbol('E', &[bar; 3]),
+	].concat();
+	let symbols = font::parse_font_symbols(&text, "Lint.txt").expect("Font should load.");
+	let findings = lint_font(&symbols, 2);
+	assert_eq!(rules(&findings), [
+		(Some(3), Some('A'), "redundant"),
+		(Some(5), Some('B'), "reversed"),
+		(Some(7), Some('C'), "duplicate"),
+		(Some(9), Some('D'), "descender"),
+		(Some(14), Some('E'), "redundant"),
+		(Some(15), Some('E'), "redundant"),
+		(Some(13), Some('E'), "budget"),
+		(Some(13), Some('E'), "duplicate"),
+	]);
+	assert_eq!(findings[1].severity, Severity::Warning);
+	assert_eq!(findings[6].severity, Severity::Error);
+	assert_eq!(findings[2].to_string(), "7: warning[duplicate] 'C': Symbol looks exactly like 'A'");
+}
+
+#[test]
+fn findings_as_json() {
+	let error = font::parse_font_symbols("key \"\n0101\n", "Bad \"font\".txt").err().expect("Font should not load.");
+	let finding = Finding::from_load_error(&error);
+	assert_eq!(finding.line, Some(2));
+	assert_eq!(
+		finding.to_json("Bad \"font\".txt"),
+		"{\"file\":\"Bad \\\"font\\\".txt\",\"line\":2,\"key\":null,\"rule\":\"load\",\"severity\":\"error\",\"message\":\"Font file has invalid line: '0101'\"}",
+	);
+	
+	let text = format_symbol('\\', &[instruction(1, 1, false, 1, 1, false)]);
+	let symbols = font::parse_font_symbols(&text, "Slash.txt").expect("Font should load.");
+	let json = lint_font(&symbols, INSTRUCTION_BUDGET)[0].to_json("Slash.txt");
+	assert!(json.starts_with("{\"file\":\"Slash.txt\",\"line\":2,\"key\":\"\\\\\",\"rule\":\"descender\","), "Unexpected JSON {}", json);
+}
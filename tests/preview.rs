@@ -0,0 +1,42 @@
+use typewriter::compiler::{compile_font, parse_ascii_glyphs};
+use typewriter::font;
+use typewriter::glyph::rasterize;
+use typewriter::preview::{diff_fonts, preview_font};
+
+#[test]
+fn preview_compiles_to_the_same_font() {
+	let built_in = font::load_font().expect("Could not load font.");
+	let preview = preview_font(&built_in);
+	assert!(preview.starts_with("// ID 1, 2 instructions\nkey A\n.###.\n#...#\n"), "Unexpected preview:\n{}", &preview[..100]);
+	
+	let glyphs = parse_ascii_glyphs(&preview).expect("Preview should parse as glyphs.");
+	assert_eq!(glyphs.len(), built_in.highest_id as usize);
+	let compiled = font::parse_font(&compile_font(&glyphs).expect("Preview should compile."), "Compiled.txt").expect("Compiled font should load.");
+	for (key, symbol) in &built_in.symbols_by_key {
+		assert_eq!(rasterize(&compiled.symbols_by_key[key]), rasterize(symbol), "Symbol '{}' changed", key);
+	}
+	assert_eq!(diff_fonts(&built_in, &compiled), "");
+}
+
+#[test]
+fn diff_shows_changed_added_and_removed_glyphs() {
+	let built_in = font::load_font().expect("Could not load font.");
+	//Swap I and l, drop everything except a few and add a new glyph:
+	let mut glyphs = parse_ascii_glyphs(&preview_font(&built_in)).expect("Preview should parse as glyphs.");
+	glyphs.retain(|(key, _)| "IlT".contains(*key));
+	let (i, l) = (glyphs[0].1, glyphs[2].1);
+	glyphs[0].1 = l;
+	glyphs[2].1 = i;
+	let mut extra = glyphs[1].1;
+	extra[10][4] = true;
+	glyphs.push(('§', extra));
+	let changed = font::parse_font(&compile_font(&glyphs).expect("Glyphs should compile."), "Changed.txt").expect("Changed font should load.");
+	
+	let diff = diff_fonts(&built_in, &changed);
+	assert!(diff.contains("key I ("), "Missing I in:\n{}", diff);
+	assert!(diff.contains("key l ("), "Missing l in:\n{}", diff);
+	assert!(!diff.contains("key T"), "T did not change:\n{}", diff);
+	assert!(diff.contains("\nAdded: §\n"), "Missing added glyph in:\n{}", diff);
+	assert!(diff.ends_with(&format!("2 changed, {} removed, 1 added\n", built_in.highest_id - 3)), "Unexpected summary in:\n{}", diff);
+	assert!(diff.lines().any(|line| line.ends_with('+')) && diff.lines().any(|line| line.ends_with('-')), "Missing changed pixels in:\n{}", diff);
+}
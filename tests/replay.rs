@@ -0,0 +1,20 @@
+use std::fs::{read_dir, read_to_string};
+use typewriter::font;
+use typewriter::replay::Replay;
+
+#[test]
+fn replay_scripts() {
+	let font = font::load_font().expect("Could not load font.");
+	let mut failures = Vec::new();
+	let mut paths = read_dir("tests/scripts").expect("Could not read script folder.")
+		.map(|entry| entry.expect("Could not read script entry.").path())
+		.collect::<Vec<_>>();
+	paths.sort();
+	for path in paths {
+		let script = read_to_string(&path).expect("Could not read script.");
+		if let Err(error) = Replay::new(&font).run(&script) {
+			failures.push(format!("{}: {}", path.display(), error));
+		}
+	}
+	assert!(failures.is_empty(), "Replay scripts diverged:\n{}", failures.join("\n"));
+}
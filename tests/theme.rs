@@ -0,0 +1,54 @@
+use typewriter::theme::{built_in_themes, load_theme_file, load_themes, parse_themes, Color, ThemeError};
+
+#[test]
+fn missing_elements_use_the_text_color() {
+	let themes = parse_themes("# Comment\n[blue]\ntext = #0000ff\nbackground = #000010\ngutter = #000080\n").unwrap();
+	assert_eq!(themes.len(), 1);
+	let theme = &themes[0];
+	assert_eq!(theme.name, "blue");
+	assert_eq!(theme.background, Color::rgb(0, 0, 0x10));
+	assert_eq!(theme.gutter, Color::rgb(0, 0, 0x80));
+	assert_eq!(theme.cursor, theme.text);
+	assert_eq!(theme.selection, theme.text);
+	assert_eq!(theme.status, theme.text);
+	
+	let themes = parse_themes("[red]\ntext = #ff0000\nbackground = #100000\nselection = #400000\n").unwrap();
+	assert_eq!(themes[0].selection, Color::rgb(0x40, 0, 0));
+}
+
+#[test]
+fn invalid_theme_files_report_the_line() {
+	assert_eq!(parse_themes("[a]\ntext = 00ff00\n"), Err(ThemeError::InvalidColor(2, "00ff00".to_string())));
+	assert_eq!(parse_themes("[a]\n\nborder = #00ff00\n"), Err(ThemeError::UnknownElement(3, "border".to_string())));
+	assert_eq!(parse_themes("text = #00ff00\n"), Err(ThemeError::InvalidLine(1, "text = #00ff00".to_string())));
+	assert_eq!(parse_themes("[a]\ntext = #00ff00\n"), Err(ThemeError::MissingColor("a".to_string())));
+}
+
+#[test]
+fn shipped_theme_file_is_built_in() {
+	let themes = load_themes().unwrap();
+	assert!(themes.len() > built_in_themes().len());
+	assert!(themes.iter().any(|theme| theme.name == "paper"));
+}
+
+#[test]
+fn theme_files_add_to_the_built_in_themes() {
+	let path = std::env::temp_dir().join(format!("typewriter-themes-{}.txt", std::process::id()));
+	std::fs::write(&path, "[green]\ntext = #00ff00\nbackground = #000000\n[blue]\ntext = #0000ff\nbackground = #000010\n").unwrap();
+	let themes = load_theme_file(&path).unwrap();
+	assert_eq!(themes.len(), load_themes().unwrap().len() + 1);
+	assert_eq!(themes[0].text, Color::rgb(0, 0xff, 0));
+	assert_eq!(themes.last().unwrap().name, "blue");
+	
+	//Errors name the file they are in:
+	std::fs::write(&path, "[a]\ntext = 00ff00\n").unwrap();
+	let error = load_theme_file(&path).unwrap_err();
+	assert_eq!(error, ThemeError::InFile { file: path.display().to_string(), error: Box::new(ThemeError::InvalidColor(2, "00ff00".to_string())) });
+	assert_eq!(error.to_string(), format!("{}: Line 2: Expected color as '#rrggbb', got '00ff00'", path.display()));
+	assert_eq!(error.summary(), "Themes L2: invalid color");
+	
+	std::fs::remove_file(&path).unwrap();
+	let error = load_theme_file(&path).unwrap_err();
+	assert!(matches!(error, ThemeError::InFile { .. }));
+	assert_eq!(error.summary(), "Themes: cannot read file");
+}
@@ -0,0 +1,126 @@
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use typewriter::config::{GridLayout, NEWLINE};
+use typewriter::font::format_symbol;
+use typewriter::input::{Key, KeyModifiers};
+use typewriter::status::{StatusLine, MESSAGE_DURATION};
+use typewriter::typewriter::{LetterInstruction, Typewriter};
+use typewriter::watch::{FontWatcher, POLL_INTERVAL};
+
+///Writes the font file with a fixed modification time, so that changes are noticed no matter how coarse the file system clock is.
+fn write_font(path: &Path, text: &str, modified: u64) {
+	std::fs::write(path, text).expect("Could not write font file.");
+	let file = OpenOptions::new().write(true).open(path).expect("Could not open font file.");
+	file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(modified)).expect("Could not set modification time.");
+}
+
+fn temp_font(name: &str) -> PathBuf {
+	std::env::temp_dir().join(format!("typewriter-{}-{}.txt", name, std::process::id()))
+}
+
+#[test]
+fn changed_font_files_are_loaded_again() {
+	let text = std::fs::read_to_string("FontBytes.txt").expect("Could not read font file.");
+	let path = temp_font("reload");
+	write_font(&path, &text, 1000);
+	let mut watcher = FontWatcher::new(&path, Duration::ZERO);
+	
+	//Checks only once per interval, and only reports changes:
+	assert_eq!(watcher.until_check(Duration::ZERO), POLL_INTERVAL);
+	assert!(watcher.poll(POLL_INTERVAL).is_none());
+	write_font(&path, &text, 2000);
+	assert!(watcher.poll(POLL_INTERVAL + Duration::from_millis(1)).is_none());
+	let font = watcher.poll(POLL_INTERVAL * 2).expect("Change should be noticed.").expect("Font should load.");
+	assert_eq!(font.ids_by_key[&'A'], 1);
+	assert!(watcher.poll(POLL_INTERVAL * 3).is_none());
+	
+	//Broken fonts are reported once, fixing them loads them again:
+	write_font(&path, &text.replacen('0', "2", 1), 3000);
+	let error = watcher.poll(POLL_INTERVAL * 4).expect("Change should be noticed.").err().expect("Broken font should fail.");
+	assert!(error.to_string().contains(":1: "), "Unexpected error '{}'", error);
+	assert!(watcher.poll(POLL_INTERVAL * 5).is_none());
+	write_font(&path, &text, 4000);
+	assert!(watcher.poll(POLL_INTERVAL * 6).expect("Change should be noticed.").is_ok());
+	
+	//Deleting the file is a change too:
+	std::fs::remove_file(&path).expect("Could not remove font file.");
+	assert!(watcher.poll(POLL_INTERVAL * 7).expect("Removal should be noticed.").is_err());
+	File::create(&path).expect("Could not create font file.");
+	assert!(watcher.poll(POLL_INTERVAL * 8).expect("Creation should be noticed.").is_err());
+	std::fs::remove_file(&path).expect("Could not remove font file.");
+}
+
+#[test]
+fn status_line_shows_messages_until_cleared_or_expired() {
+	let font = typewriter::font::load_font().expect("Could not load built-in font.");
+	let typewriter = Typewriter::new(GridLayout::with_size(20, 4).expect("Grid should be valid."));
+	let mut status_line = StatusLine::default();
+	let text = |letter_instructions: &[LetterInstruction]| letter_instructions.iter().map(|instruction| font.keys_by_id[&instruction.id]).collect::<String>();
+	
+	let mut letter_instructions = Vec::new();
+	status_line.show_message("ERROR".to_string(), Duration::ZERO);
+	status_line.update(&typewriter, &font, &mut letter_instructions);
+	assert_eq!(text(&letter_instructions), "ERROR");
+	assert_eq!(status_line.until_expiry(Duration::from_secs(1)), Some(MESSAGE_DURATION - Duration::from_secs(1)));
+	
+	//Redrawn after the canvas got cleared:
+	letter_instructions.clear();
+	status_line.reset();
+	status_line.update(&typewriter, &font, &mut letter_instructions);
+	assert_eq!(text(&letter_instructions).trim_end(), "ERROR");
+	
+	//Still shown shortly before its time is over:
+	letter_instructions.clear();
+	status_line.expire(MESSAGE_DURATION - Duration::from_millis(1));
+	status_line.update(&typewriter, &font, &mut letter_instructions);
+	assert!(letter_instructions.is_empty());
+	
+	status_line.expire(MESSAGE_DURATION);
+	status_line.update(&typewriter, &font, &mut letter_instructions);
+	assert!(text(&letter_instructions).starts_with("L1 C1"));
+	assert_eq!(status_line.until_expiry(MESSAGE_DURATION), None);
+	
+	letter_instructions.clear();
+	status_line.show_message("ERROR".to_string(), MESSAGE_DURATION);
+	status_line.clear_message();
+	status_line.update(&typewriter, &font, &mut letter_instructions);
+	assert!(letter_instructions.is_empty());
+}
+
+#[test]
+fn reloaded_fonts_need_all_letters_of_the_text() {
+	let built_in = typewriter::font::load_font().expect("Could not load built-in font.");
+	let font_of = |keys: &str| keys.chars().map(|key| format_symbol(key, &built_in.symbols_by_key[&key])).collect::<String>();
+	let path = temp_font("reorder");
+	write_font(&path, &font_of("ABC"), 1000);
+	let old = typewriter::font::load_font_file(&path).expect("Font should load.");
+	let mut watcher = FontWatcher::new(&path, Duration::ZERO);
+	
+	let mut typewriter = Typewriter::new(GridLayout::default());
+	let mut letter_instructions = Vec::new();
+	for letter in "AB C".chars() {
+		typewriter.type_letter(&letter, &mut letter_instructions, &old).expect("Typing should work.");
+	}
+	typewriter.handle_input(&Key::Return, &KeyModifiers::NONE, &mut letter_instructions, &old).expect("Return should work.");
+	typewriter.type_letter(&'C', &mut letter_instructions, &old).expect("Typing should work.");
+	typewriter.handle_input(&Key::Left, &KeyModifiers::NONE, &mut letter_instructions, &old).expect("Moving should work.");
+	
+	//Without the B, the font cannot be used without losing text:
+	write_font(&path, &font_of("DCA"), 2000);
+	let new = watcher.poll(POLL_INTERVAL).expect("Change should be noticed.").expect("Font should load.");
+	assert_eq!(old.missing_letter(&typewriter.memory.contents(), &new), Some('B'));
+	
+	//Reordered and with a new symbol, the IDs are translated:
+	write_font(&path, &font_of("DCBA"), 3000);
+	let new = watcher.poll(POLL_INTERVAL * 2).expect("Change should be noticed.").expect("Font should load.");
+	assert_eq!(old.missing_letter(&typewriter.memory.contents(), &new), None);
+	typewriter.memory.map_symbols(|id| old.translate_id(id, &new));
+	let text = typewriter.memory.contents().iter()
+		.map(|id| if *id == NEWLINE { '\n' } else { new.keys_by_id[id] })
+		.collect::<String>();
+	assert_eq!(text, "AB C\nC");
+	assert_eq!(typewriter.memory.contents()[0], new.id('A'));
+	assert_ne!(new.id('A'), old.id('A'));
+	std::fs::remove_file(&path).expect("Could not remove font file.");
+}